@@ -131,6 +131,18 @@ fn solve(input: &str) -> i64 {
     }
 }
 
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    if args.len() < 2 {
+        eprintln!("Usage: {} <input_file>", args[0]);
+        std::process::exit(1);
+    }
+
+    let content = std::fs::read_to_string(&args[1]).expect("Failed to read input file");
+    let result = solve(content.trim());
+    println!("Result: {}", result);
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -167,18 +179,6 @@ mod tests {
             fs::read_to_string("docs/challenge_1.txt").expect("Failed to read input file");
 
         // Call the algorithm and check the output
-        assert_eq!(solve(&content.trim()), 6323641412437);
+        assert_eq!(solve(content.trim()), 6323641412437);
     }
 }
-
-fn main() {
-    let args: Vec<String> = std::env::args().collect();
-    if args.len() < 2 {
-        eprintln!("Usage: {} <input_file>", args[0]);
-        std::process::exit(1);
-    }
-
-    let content = std::fs::read_to_string(&args[1]).expect("Failed to read input file");
-    let result = solve(content.trim());
-    println!("Result: {}", result);
-}
@@ -1,134 +1,131 @@
-/// Represents an item on the disk, either a file or free space.
-#[derive(Copy, Clone, Debug)]
-enum DiskItem {
-    File(i64, i64), // File with its ID and size.
-    FreeSpace(i64), // Free space with its size.
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+/// Longest span a single digit of the disk map can describe.
+const MAX_SPAN: usize = 9;
+
+/// A contiguous run of blocks on the disk.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+struct Span {
+    start: usize, // Position of the first block.
+    len: usize,   // Number of blocks.
 }
 
-/// Parses the input string into a vector of `DiskItem`s.
-fn parse(input: &str) -> Vec<DiskItem> {
-    let mut file_id = 0;
-    input
-        .trim()
-        .chars()
-        .enumerate()
-        .map(|(index, char)| {
-            let size: i64 = char.to_digit(10).unwrap() as i64;
-            if index % 2 == 0 {
-                assert!(size > 0, "File size must be greater than 0");
-                let item = DiskItem::File(file_id, size);
-                file_id += 1;
-                item
-            } else {
-                DiskItem::FreeSpace(size)
-            }
-        })
-        .collect()
+/// Parses the disk map into the span of each file, indexed by file ID, and the free spans
+/// between them, in disk order.
+fn parse(input: &str) -> (Vec<Span>, Vec<Span>) {
+    let mut files = Vec::with_capacity(input.len() / 2 + 1);
+    let mut free = Vec::with_capacity(input.len() / 2);
+    let mut position = 0;
+
+    for (index, char) in input.trim().chars().enumerate() {
+        let len = char.to_digit(10).unwrap() as usize;
+        let span = Span {
+            start: position,
+            len,
+        };
+        if index % 2 == 0 {
+            assert!(len > 0, "File size must be greater than 0");
+            files.push(span);
+        } else {
+            free.push(span);
+        }
+        position += len;
+    }
+
+    (files, free)
 }
 
-/// Consolidates consecutive free spaces into a single free space item.
-fn consolidate_items(items: &mut Vec<DiskItem>, temp_storage: &mut Vec<DiskItem>) {
-    // Move items from the main vector to temporary storage in reverse order.
-    while let Some(item) = items.pop() {
-        temp_storage.push(item);
+/// Free spans indexed by length: `heaps[len]` holds the start of every free span of exactly
+/// `len` blocks, leftmost first. The leftmost span that fits a file is the smallest start
+/// among the heaps for its size and above, so finding it takes at most nine peeks.
+struct FreeSpans {
+    heaps: [BinaryHeap<Reverse<usize>>; MAX_SPAN + 1],
+}
+
+impl FreeSpans {
+    /// Indexes the free spans of a freshly parsed disk.
+    fn new(spans: &[Span]) -> Self {
+        let mut free = FreeSpans {
+            heaps: Default::default(),
+        };
+        for &span in spans {
+            free.insert(span);
+        }
+        free
     }
 
-    // Merge items back from the temporary storage into the main vector.
-    while let Some(item) = temp_storage.pop() {
-        if items.is_empty() {
-            items.push(item);
-            continue;
+    /// Adds a free span; empty spans are dropped.
+    fn insert(&mut self, span: Span) {
+        if span.len > 0 {
+            self.heaps[span.len].push(Reverse(span.start));
         }
+    }
 
-        match item {
-            DiskItem::File(_, _) => items.push(item),
-            DiskItem::FreeSpace(size) => {
-                if let Some(DiskItem::FreeSpace(existing_size)) = items.last_mut() {
-                    *existing_size += size; // Merge with the previous free space.
-                } else {
-                    items.push(DiskItem::FreeSpace(size));
-                }
-            }
+    /// Removes and returns the leftmost free span of at least `len` blocks that starts
+    /// before `limit`.
+    fn take_leftmost(&mut self, len: usize, limit: usize) -> Option<Span> {
+        let (start, len) = (len..=MAX_SPAN)
+            .filter_map(|len| self.heaps[len].peek().map(|&Reverse(start)| (start, len)))
+            .min()?;
+        if start >= limit {
+            return None;
         }
+        self.heaps[len].pop();
+        Some(Span { start, len })
     }
 }
 
-/// Solves the problem by processing input to find the checksum of the compacted disk.
-fn solve(input: &str) -> i64 {
-    let mut items: Vec<DiskItem> = parse(input);
-    let mut temp_storage: Vec<DiskItem> = Vec::with_capacity(items.len());
-    let item_count = items.len();
-
-    // Iterate through items from right to left to move files.
-    for index in (0..item_count).rev() {
-        match items[index] {
-            DiskItem::FreeSpace(_) => continue, // Skip free space.
-            DiskItem::File(_, size) => {
-                // Search for a suitable free space to the left.
-                let mut slot_index = 0;
-                let mut found = false;
-                let mut empty_space_size = 0;
-
-                // for j in 0..index {
-                for (j, _item) in items.iter().enumerate().take(index) {
-                    if let DiskItem::FreeSpace(space) = items[j] {
-                        if space >= size {
-                            slot_index = j;
-                            found = true;
-                            empty_space_size = space;
-                            break;
-                        }
-                    }
-                }
-
-                // If a suitable slot was found, move the file to it.
-                if found {
-                    if empty_space_size == size {
-                        items[slot_index] = items[index];
-                        items[index] = DiskItem::FreeSpace(size);
-                    } else {
-                        // Insert the file at the found slot and split the free space.
-                        items.insert(slot_index, items[index]);
-                        items[slot_index + 1] = DiskItem::FreeSpace(empty_space_size - size);
-                        items[index + 1] = DiskItem::FreeSpace(size);
-                    }
-                }
-            }
+/// Moves every file, highest ID first, into the leftmost free span to its left that is
+/// large enough, leaving it in place if there is none. Each move is O(log n).
+///
+/// The space a file leaves behind is never reused: every file still to move lies to its
+/// left, so only spans left of the moved file matter and those never merge with it.
+fn compact_files(files: &mut [Span], free: &[Span]) {
+    let mut free = FreeSpans::new(free);
+
+    for file in files.iter_mut().rev() {
+        if let Some(span) = free.take_leftmost(file.len, file.start) {
+            file.start = span.start;
+            free.insert(Span {
+                start: span.start + file.len,
+                len: span.len - file.len,
+            });
         }
-
-        // Consolidate consecutive free spaces after processing each item.
-        consolidate_items(&mut items, &mut temp_storage);
     }
+}
 
-    // Calculate the checksum of the final state.
-    let (_, checksum) = calculate_checksum(&items);
-
-    checksum
+/// Solves the problem by processing input to find the checksum of the compacted disk.
+fn solve(input: &str) -> u128 {
+    let (mut files, free) = parse(input);
+    compact_files(&mut files, &free);
+    calculate_checksum(&files)
 }
 
-/// Calculates the checksum of the items on the disk.
-fn calculate_checksum(items: &[DiskItem]) -> (i32, i64) {
-    let mut checksum = 0;
-    let mut position = 0;
-    let mut offset = 0;
+/// Calculates the checksum of the files on the disk, given the span of each file by ID.
+/// The checksum is accumulated in a `u128` so that disk maps with millions of digits fit.
+fn calculate_checksum(files: &[Span]) -> u128 {
+    files
+        .iter()
+        .enumerate()
+        .map(|(id, file)| {
+            // Sum of the positions the file covers, times its ID.
+            let (start, len) = (file.start as u128, file.len as u128);
+            id as u128 * (start * len + (len - 1) * len / 2)
+        })
+        .sum()
+}
 
-    for item in items {
-        match item {
-            DiskItem::FreeSpace(space) => {
-                position += 1;
-                offset += space;
-            }
-            DiskItem::File(id, size) => {
-                // Calculate partial sum for the file size.
-                let partial_sum = (size - 1) * size / 2;
-                checksum += id * (offset * size + partial_sum);
-                position += 1;
-                offset += size;
-            }
-        }
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    if args.len() < 2 {
+        eprintln!("Usage: {} <input_file>", args[0]);
+        std::process::exit(1);
     }
 
-    (position, checksum)
+    let content = std::fs::read_to_string(&args[1]).expect("Failed to read input file");
+    let result = solve(&content);
+    println!("Result: {}", result);
 }
 
 #[cfg(test)]
@@ -164,18 +161,74 @@ mod tests {
     fn test_with_file_input() {
         let content =
             fs::read_to_string("docs/challenge_2.txt").expect("Failed to read input file");
-        assert_eq!(solve(&content.trim()), 6351801932670);
+        assert_eq!(solve(&content), 6351801932670);
     }
-}
 
-fn main() {
-    let args: Vec<String> = std::env::args().collect();
-    if args.len() < 2 {
-        eprintln!("Usage: {} <input_file>", args[0]);
-        std::process::exit(1);
+    #[test]
+    fn test_moves_into_leftmost_span() {
+        // File 2 takes the leftmost span rather than the exact fit, and file 1 the rest of it.
+        let (mut files, free) = parse("13111");
+        compact_files(&mut files, &free);
+        assert_eq!(files[2], Span { start: 1, len: 1 });
+        assert_eq!(files[1], Span { start: 2, len: 1 });
     }
 
-    let content = std::fs::read_to_string(&args[1]).expect("Failed to read input file");
-    let result = solve(content.trim());
-    println!("Result: {}", result);
+    #[test]
+    fn test_matches_linear_scan() {
+        // Compare with moving each file by scanning every free block from the left.
+        let input: String = (0..2_000)
+            .map(|i: u64| {
+                char::from_digit(
+                    ((i * 7919 + i / 3) % 9 + i.is_multiple_of(2) as u64) as u32,
+                    10,
+                )
+                .unwrap()
+            })
+            .collect();
+        let (files, free) = parse(&input);
+        let disk_len = files.iter().chain(&free).map(|span| span.len).sum();
+        let mut blocks: Vec<Option<usize>> = vec![None; disk_len];
+        for (id, file) in files.iter().enumerate() {
+            blocks[file.start..file.start + file.len].fill(Some(id));
+        }
+        for (id, file) in files.iter().enumerate().rev() {
+            let target = (0..file.start)
+                .find(|&start| blocks[start..start + file.len].iter().all(Option::is_none));
+            if let Some(target) = target {
+                blocks[file.start..file.start + file.len].fill(None);
+                blocks[target..target + file.len].fill(Some(id));
+            }
+        }
+        let expected: u128 = blocks
+            .iter()
+            .enumerate()
+            .map(|(position, id)| (position * id.unwrap_or(0)) as u128)
+            .sum();
+
+        assert_eq!(solve(&input), expected);
+    }
+
+    #[test]
+    fn test_millions_of_digits() {
+        let input: String = (0..2_000_000)
+            .map(|i: u64| char::from_digit(((i * 31 + i / 7) % 9 + 1) as u32, 10).unwrap())
+            .collect();
+        let (mut files, free) = parse(&input);
+        compact_files(&mut files, &free);
+
+        // Files never move right, and no two files overlap after compaction.
+        let (original, _) = parse(&input);
+        assert!(
+            files
+                .iter()
+                .zip(&original)
+                .all(|(moved, file)| moved.start <= file.start)
+        );
+        files.sort_by_key(|file| file.start);
+        assert!(
+            files
+                .windows(2)
+                .all(|pair| pair[0].start + pair[0].len <= pair[1].start)
+        );
+    }
 }
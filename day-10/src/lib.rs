@@ -0,0 +1,345 @@
+use std::fmt;
+
+/// Height at which every hiking trail ends.
+pub const PEAK: u8 = 9;
+
+/// Problem found while reading a topographic map.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseError {
+    /// The input has no rows.
+    Empty,
+    /// A row is not as long as the first one.
+    RaggedRow {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+    /// A cell is neither a height digit nor `.`.
+    InvalidCell { row: usize, col: usize, found: char },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::Empty => write!(f, "the map is empty"),
+            ParseError::RaggedRow {
+                row,
+                expected,
+                found,
+            } => write!(f, "row {row} has {found} cells instead of {expected}"),
+            ParseError::InvalidCell { row, col, found } => {
+                write!(
+                    f,
+                    "cell ({row}, {col}) holds '{found}', not a height or '.'"
+                )
+            }
+        }
+    }
+}
+
+/// Topographic map of heights 0 to 9, stored row by row. Cells marked `.` are impassable
+/// and have no height.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TopoMap {
+    heights: Vec<Option<u8>>,
+    rows: usize,
+    cols: usize,
+}
+
+impl TopoMap {
+    /// Reads the map from the puzzle input, one row of digits or `.` per line.
+    pub fn parse(input: &str) -> Result<Self, ParseError> {
+        let lines: Vec<&str> = input
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect();
+        let cols = lines.first().ok_or(ParseError::Empty)?.chars().count();
+
+        let mut heights = Vec::with_capacity(lines.len() * cols);
+        for (row, line) in lines.iter().enumerate() {
+            let found = line.chars().count();
+            if found != cols {
+                return Err(ParseError::RaggedRow {
+                    row,
+                    expected: cols,
+                    found,
+                });
+            }
+            for (col, cell) in line.chars().enumerate() {
+                heights.push(match cell {
+                    '.' => None,
+                    _ => Some(cell.to_digit(10).ok_or(ParseError::InvalidCell {
+                        row,
+                        col,
+                        found: cell,
+                    })? as u8),
+                });
+            }
+        }
+
+        Ok(TopoMap {
+            heights,
+            rows: lines.len(),
+            cols,
+        })
+    }
+
+    /// Number of rows on the map.
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Number of columns on the map.
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// Height of a cell, or `None` if it is impassable.
+    pub fn height(&self, row: usize, col: usize) -> Option<u8> {
+        self.heights[row * self.cols + col]
+    }
+
+    /// Row and column of a cell index.
+    fn position(&self, cell: usize) -> (usize, usize) {
+        (cell / self.cols, cell % self.cols)
+    }
+
+    /// Neighbours of a cell exactly one step higher, the only cells a trail can move to.
+    fn uphill(&self, cell: usize) -> impl Iterator<Item = usize> + '_ {
+        let (row, col) = self.position(cell);
+        let next = self.heights[cell].map(|height| height + 1);
+        [
+            (row > 0).then(|| cell - self.cols),
+            (col > 0).then(|| cell - 1),
+            (row + 1 < self.rows).then(|| cell + self.cols),
+            (col + 1 < self.cols).then(|| cell + 1),
+        ]
+        .into_iter()
+        .flatten()
+        .filter(move |&neighbour| next.is_some() && self.heights[neighbour] == next)
+    }
+
+    /// Scores and rates every trailhead in one pass over the heights from 9 down to 0.
+    ///
+    /// Each cell learns from its uphill neighbours, which are all done by then, how many
+    /// trails lead from it to a peak and which peaks those reach. A cell's rating is the
+    /// sum of its neighbours' ratings; its peaks are the union of theirs, kept as a
+    /// sorted list so only the level above needs to stay in memory.
+    pub fn analyse(&self) -> TrailAnalysis<'_> {
+        let mut levels: Vec<Vec<usize>> = vec![Vec::new(); PEAK as usize + 1];
+        for (cell, height) in self.heights.iter().enumerate() {
+            if let Some(height) = height {
+                levels[*height as usize].push(cell);
+            }
+        }
+
+        let mut ratings = vec![0; self.heights.len()];
+        let mut peaks: Vec<Vec<u32>> = vec![Vec::new(); self.heights.len()];
+        for &cell in &levels[PEAK as usize] {
+            ratings[cell] = 1;
+            peaks[cell] = vec![cell as u32];
+        }
+
+        for height in (0..PEAK as usize).rev() {
+            for &cell in &levels[height] {
+                let mut reached = Vec::new();
+                for neighbour in self.uphill(cell) {
+                    ratings[cell] += ratings[neighbour];
+                    reached.extend_from_slice(&peaks[neighbour]);
+                }
+                reached.sort_unstable();
+                reached.dedup();
+                peaks[cell] = reached;
+            }
+
+            // The level above has passed everything on, so its peak lists can go
+            for &cell in &levels[height + 1] {
+                peaks[cell] = Vec::new();
+            }
+        }
+
+        let trailheads = levels[0]
+            .iter()
+            .map(|&cell| Trailhead {
+                position: self.position(cell),
+                score: peaks[cell].len(),
+                rating: ratings[cell],
+            })
+            .collect();
+
+        TrailAnalysis {
+            map: self,
+            ratings,
+            trailheads,
+        }
+    }
+}
+
+/// A trailhead with its score, the number of peaks its trails reach, and its rating, the
+/// number of distinct trails that start from it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Trailhead {
+    pub position: (usize, usize),
+    pub score: usize,
+    pub rating: u64,
+}
+
+/// Scores and ratings of every trailhead of a map, from [`TopoMap::analyse`].
+#[derive(Clone, Debug)]
+pub struct TrailAnalysis<'a> {
+    map: &'a TopoMap,
+    ratings: Vec<u64>,
+    trailheads: Vec<Trailhead>,
+}
+
+impl TrailAnalysis<'_> {
+    /// Every trailhead, in reading order.
+    pub fn trailheads(&self) -> &[Trailhead] {
+        &self.trailheads
+    }
+
+    /// Sum of the scores of all trailheads.
+    pub fn total_score(&self) -> usize {
+        self.trailheads.iter().map(|head| head.score).sum()
+    }
+
+    /// Sum of the ratings of all trailheads.
+    pub fn total_rating(&self) -> u64 {
+        self.trailheads.iter().map(|head| head.rating).sum()
+    }
+
+    /// Every distinct trail from a trailhead to a peak, as the cells it climbs through.
+    /// Only cells with a rating are followed, so no branch is explored that cannot finish.
+    pub fn trails(&self, trailhead: (usize, usize)) -> Vec<Vec<(usize, usize)>> {
+        let map = self.map;
+        let start = trailhead.0 * map.cols + trailhead.1;
+        let mut trails = Vec::new();
+        if map.heights[start] != Some(0) {
+            return trails;
+        }
+
+        let mut path = vec![start];
+        self.extend_trails(&mut path, &mut trails);
+        trails
+    }
+
+    /// Depth-first search adding every completion of the partial trail `path`.
+    fn extend_trails(&self, path: &mut Vec<usize>, trails: &mut Vec<Vec<(usize, usize)>>) {
+        let cell = *path.last().unwrap();
+        if self.map.heights[cell] == Some(PEAK) {
+            trails.push(path.iter().map(|&step| self.map.position(step)).collect());
+            return;
+        }
+        for neighbour in self.map.uphill(cell) {
+            if self.ratings[neighbour] > 0 {
+                path.push(neighbour);
+                self.extend_trails(path, trails);
+                path.pop();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LARGER_EXAMPLE: &str = "89010123
+78121874
+87430965
+96549874
+45678903
+32019012
+01329801
+10456732";
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(TopoMap::parse(" \n"), Err(ParseError::Empty));
+        assert_eq!(
+            TopoMap::parse("012\n34"),
+            Err(ParseError::RaggedRow {
+                row: 1,
+                expected: 3,
+                found: 2
+            })
+        );
+        assert_eq!(
+            TopoMap::parse("01\n2x"),
+            Err(ParseError::InvalidCell {
+                row: 1,
+                col: 1,
+                found: 'x'
+            })
+        );
+    }
+
+    #[test]
+    fn test_impassable_cells() {
+        let map = TopoMap::parse("10..9..\n2...8..\n3...7..\n4567654\n...8..3\n...9..2\n.....01")
+            .unwrap();
+        assert_eq!(map.height(0, 2), None);
+        let analysis = map.analyse();
+        let scores: Vec<_> = analysis
+            .trailheads()
+            .iter()
+            .map(|head| (head.position, head.score))
+            .collect();
+        assert_eq!(scores, vec![((0, 1), 1), ((6, 5), 2)]);
+    }
+
+    #[test]
+    fn test_scores_and_ratings() {
+        let map = TopoMap::parse(LARGER_EXAMPLE).unwrap();
+        let analysis = map.analyse();
+        assert_eq!(analysis.trailheads().len(), 9);
+        assert_eq!(analysis.total_score(), 36);
+        assert_eq!(analysis.total_rating(), 81);
+        assert_eq!(
+            analysis.trailheads()[0],
+            Trailhead {
+                position: (0, 2),
+                score: 5,
+                rating: 20
+            }
+        );
+    }
+
+    #[test]
+    fn test_trails() {
+        let map = TopoMap::parse(".....0.\n..4321.\n..5..2.\n..6543.\n..7..4.\n..8765.\n..9....")
+            .unwrap();
+        let analysis = map.analyse();
+        let trails = analysis.trails((0, 5));
+        assert_eq!(trails.len(), 3);
+        assert!(trails.contains(&vec![
+            (0, 5),
+            (1, 5),
+            (2, 5),
+            (3, 5),
+            (4, 5),
+            (5, 5),
+            (5, 4),
+            (5, 3),
+            (5, 2),
+            (6, 2)
+        ]));
+        assert!(analysis.trails((0, 0)).is_empty());
+
+        // Every trail of the larger example is distinct and climbs one step at a time
+        let map = TopoMap::parse(LARGER_EXAMPLE).unwrap();
+        let analysis = map.analyse();
+        for head in analysis.trailheads() {
+            let mut trails = analysis.trails(head.position);
+            assert_eq!(trails.len() as u64, head.rating);
+            for trail in &trails {
+                let heights: Vec<_> = trail.iter().map(|&(r, c)| map.height(r, c)).collect();
+                assert_eq!(heights, (0..=PEAK).map(Some).collect::<Vec<_>>());
+            }
+            trails.sort();
+            trails.dedup();
+            assert_eq!(trails.len() as u64, head.rating);
+        }
+    }
+}
@@ -1,69 +1,57 @@
-use std::{collections::HashMap, env, fs};
+use day_11::{BigCount, BlinkError, RuleSet, count_stones, parse_stones};
+use std::{env, fs};
 
 fn main() {
     // Collect command-line arguments, expecting the first argument to be the input file name.
     let args: Vec<String> = env::args().collect();
     if args.len() < 2 {
-        eprintln!("Usage: {} <input_file>", args[0]);
-        std::process::exit(1);
+        usage(&args[0], "Missing input file");
     }
 
     // Read the content of the input file.
     let input = fs::read_to_string(&args[1]).expect("Failed to read input file");
 
-    // Parse numbers from the input file.
-    let stones: Vec<u64> = input
-        .split_whitespace()
-        .map(|num| num.parse::<u64>().unwrap())
-        .collect();
+    // Use the puzzle's rules unless a rule configuration is given.
+    let rules = match args.get(2).map(String::as_str) {
+        Some("--rules") => match args.get(3).map(|path| read_rules(path)) {
+            Some(Ok(rules)) => rules,
+            Some(Err(error)) => usage(&args[0], &error),
+            None => usage(&args[0], "--rules takes a file"),
+        },
+        Some(other) => usage(&args[0], &format!("Unknown option {other}")),
+        None => RuleSet::default(),
+    };
 
-    println!("Part 1: {}", calculate_total_stones(&stones, 25));
+    match calculate_total_stones(&input, &rules, 25) {
+        Ok(total) => println!("Part 1: {}", total),
+        Err(error) => {
+            eprintln!("Cannot blink: {error}");
+            std::process::exit(1);
+        }
+    }
 }
 
-// Calculates the total number of stones after a given number of blinks.
-fn calculate_total_stones(stones: &[u64], blinks: u8) -> u64 {
-    let mut memoization_table: HashMap<(u64, u8), u64> = HashMap::new();
-
-    stones
-        .iter()
-        .map(|&stone| evolve_stone(stone, blinks, &mut memoization_table))
-        .sum()
+// Prints what is wrong with the arguments and how to use the program, then exits.
+fn usage(program: &str, problem: &str) -> ! {
+    eprintln!("{problem}");
+    eprintln!("Usage: {program} <input_file> [--rules <rules_file>]");
+    std::process::exit(1);
 }
 
-// Computes the number of stones resulting from evolving a single stone.
-fn evolve_stone(stone: u64, blinks: u8, memoization_table: &mut HashMap<(u64, u8), u64>) -> u64 {
-    // Check for cached results.
-    if let Some(&cached_result) = memoization_table.get(&(stone, blinks)) {
-        return cached_result;
-    }
-
-    // Base case: no more blinks left.
-    if blinks == 0 {
-        return 1;
-    }
-
-    let result = if stone == 0 {
-        // Rule 1: Replace a stone marked 0 with a stone marked 1.
-        evolve_stone(1, blinks - 1, memoization_table)
-    } else {
-        let digit_count = ((stone as f64).log10() + 1.0) as u32;
-
-        if digit_count % 2 == 0 {
-            // Rule 2: Split the stone into two halves if it has an even number of digits.
-            let left_half = stone / 10u64.pow(digit_count / 2);
-            let right_half = stone % 10u64.pow(digit_count / 2);
-
-            evolve_stone(left_half, blinks - 1, memoization_table)
-                + evolve_stone(right_half, blinks - 1, memoization_table)
-        } else {
-            // Rule 3: Multiply the stone by 2024 if no other rules apply.
-            evolve_stone(stone * 2024, blinks - 1, memoization_table)
-        }
-    };
+// Reads and parses a rule configuration file.
+fn read_rules(path: &str) -> Result<RuleSet, String> {
+    let config =
+        fs::read_to_string(path).map_err(|error| format!("Cannot read {path}: {error}"))?;
+    RuleSet::parse(&config).map_err(|error| format!("Invalid rules: {error}"))
+}
 
-    // Cache the result for this stone and blink count.
-    memoization_table.insert((stone, blinks), result);
-    result
+// Calculates the total number of stones after a given number of blinks.
+fn calculate_total_stones(
+    input: &str,
+    rules: &RuleSet,
+    blinks: usize,
+) -> Result<BigCount, BlinkError> {
+    count_stones(&parse_stones(input), rules, blinks)
 }
 
 #[cfg(test)]
@@ -72,22 +60,16 @@ mod tests {
 
     #[test]
     fn test_calculate_total_stones_sample() {
-        let stones: Vec<u64> = "125 17"
-            .split_whitespace()
-            .map(|num| num.parse::<u64>().unwrap())
-            .collect();
+        let total = calculate_total_stones("125 17", &RuleSet::default(), 25).unwrap();
 
-        assert_eq!(calculate_total_stones(&stones, 25), 55312);
+        assert_eq!(total.to_u128(), Some(55312));
     }
 
     #[test]
     fn test_calculate_total_stones_challenge() {
         let challenge_input: &str = include_str!("../../docs/challenge_1.txt");
-        let stones: Vec<u64> = challenge_input
-            .split_whitespace()
-            .map(|num| num.parse::<u64>().unwrap())
-            .collect();
+        let total = calculate_total_stones(challenge_input, &RuleSet::default(), 25).unwrap();
 
-        assert_eq!(calculate_total_stones(&stones, 25), 209412);
+        assert_eq!(total.to_u128(), Some(209412));
     }
 }
@@ -1,69 +1,105 @@
-use std::{collections::HashMap, env, fs};
+use day_11::{BlinkError, Evolution, RuleSet, StoneGraph, growth_rate, parse_stones};
+use std::{env, fs};
+
+// Most distinct stones explored before a rule set is considered to grow without bound.
+const STONE_LIMIT: usize = 1_000_000;
 
 fn main() {
     // Collect command-line arguments, expecting the first argument to be the input file name.
     let args: Vec<String> = env::args().collect();
     if args.len() < 2 {
-        eprintln!("Usage: {} <input_file>", args[0]);
-        std::process::exit(1);
+        usage(&args[0], "Missing input file");
     }
 
     // Read the content of the input file.
     let input = fs::read_to_string(&args[1]).expect("Failed to read input file");
+    let stones = parse_stones(&input);
+
+    let mut rules = RuleSet::default();
+    let mut blinks = 75;
+    let mut graph = false;
+    let mut growth = false;
+    let mut options = args[2..].iter();
+    while let Some(option) = options.next() {
+        match option.as_str() {
+            "--rules" => {
+                rules = match options.next().map(|path| read_rules(path)) {
+                    Some(Ok(rules)) => rules,
+                    Some(Err(error)) => usage(&args[0], &error),
+                    None => usage(&args[0], "--rules takes a file"),
+                };
+            }
+            "--blinks" => {
+                blinks = options
+                    .next()
+                    .and_then(|value| value.parse().ok())
+                    .unwrap_or_else(|| usage(&args[0], "--blinks takes a number of blinks"));
+            }
+            "--graph" => graph = true,
+            "--growth" => growth = true,
+            other => usage(&args[0], &format!("Unknown option {other}")),
+        }
+    }
 
-    // Parse numbers from the input file.
-    let stones: Vec<u64> = input
-        .split_whitespace()
-        .map(|num| num.parse::<u64>().unwrap())
-        .collect();
-
-    println!("Part 2: {}", calculate_total_stones(&stones, 75));
+    if let Err(error) = run(&stones, rules, blinks, graph, growth) {
+        eprintln!("Cannot blink: {error}");
+        std::process::exit(1);
+    }
 }
 
-// Calculates the total number of stones after a given number of blinks.
-fn calculate_total_stones(stones: &[u64], blinks: u8) -> u64 {
-    let mut memoization_table: HashMap<(u64, u8), u64> = HashMap::new();
+// Prints what is wrong with the arguments and how to use the program, then exits.
+fn usage(program: &str, problem: &str) -> ! {
+    eprintln!("{problem}");
+    eprintln!(
+        "Usage: {program} <input_file> [--rules <rules_file>] [--blinks N] [--graph] [--growth]"
+    );
+    std::process::exit(1);
+}
 
-    stones
-        .iter()
-        .map(|&stone| evolve_stone(stone, blinks, &mut memoization_table))
-        .sum()
+// Reads and parses a rule configuration file.
+fn read_rules(path: &str) -> Result<RuleSet, String> {
+    let config =
+        fs::read_to_string(path).map_err(|error| format!("Cannot read {path}: {error}"))?;
+    RuleSet::parse(&config).map_err(|error| format!("Invalid rules: {error}"))
 }
 
-// Computes the number of stones resulting from evolving a single stone.
-fn evolve_stone(stone: u64, blinks: u8, memoization_table: &mut HashMap<(u64, u8), u64>) -> u64 {
-    // Check for cached results.
-    if let Some(&cached_result) = memoization_table.get(&(stone, blinks)) {
-        return cached_result;
+// Prints the graph of reachable stones, the growth statistics or the total number of stones.
+fn run(
+    stones: &[u64],
+    rules: RuleSet,
+    blinks: usize,
+    graph: bool,
+    growth: bool,
+) -> Result<(), BlinkError> {
+    if graph {
+        print!(
+            "{}",
+            StoneGraph::closure(stones, rules, STONE_LIMIT)?.to_dot()
+        );
+        return Ok(());
     }
-
-    // Base case: no more blinks left.
-    if blinks == 0 {
-        return 1;
+    if growth {
+        let reachable = StoneGraph::closure(stones, rules.clone(), STONE_LIMIT)?;
+        println!("Reachable stones: {}", reachable.len());
+        println!(
+            "Growth per blink: {:.6}",
+            growth_rate(stones, &rules, 1000)?
+        );
     }
 
-    let result = if stone == 0 {
-        // Rule 1: Replace a stone marked 0 with a stone marked 1.
-        evolve_stone(1, blinks - 1, memoization_table)
-    } else {
-        let digit_count = ((stone as f64).log10() + 1.0) as u32;
-
-        if digit_count % 2 == 0 {
-            // Rule 2: Split the stone into two halves if it has an even number of digits.
-            let left_half = stone / 10u64.pow(digit_count / 2);
-            let right_half = stone % 10u64.pow(digit_count / 2);
-
-            evolve_stone(left_half, blinks - 1, memoization_table)
-                + evolve_stone(right_half, blinks - 1, memoization_table)
-        } else {
-            // Rule 3: Multiply the stone by 2024 if no other rules apply.
-            evolve_stone(stone * 2024, blinks - 1, memoization_table)
-        }
-    };
+    println!("Part 2: {}", calculate_total_stones(stones, rules, blinks)?);
+    Ok(())
+}
 
-    // Cache the result for this stone and blink count.
-    memoization_table.insert((stone, blinks), result);
-    result
+// Calculates the total number of stones after a given number of blinks.
+fn calculate_total_stones(
+    stones: &[u64],
+    rules: RuleSet,
+    blinks: usize,
+) -> Result<String, BlinkError> {
+    let mut evolution = Evolution::new(stones, rules);
+    evolution.advance_to(blinks)?;
+    Ok(evolution.total().to_string())
 }
 
 #[cfg(test)]
@@ -72,22 +108,22 @@ mod tests {
 
     #[test]
     fn test_calculate_total_stones_sample() {
-        let stones: Vec<u64> = "125 17"
-            .split_whitespace()
-            .map(|num| num.parse::<u64>().unwrap())
-            .collect();
+        let stones = parse_stones("125 17");
 
-        assert_eq!(calculate_total_stones(&stones, 75), 65601038650482);
+        assert_eq!(
+            calculate_total_stones(&stones, RuleSet::default(), 75),
+            Ok("65601038650482".to_string())
+        );
     }
 
     #[test]
     fn test_calculate_total_stones_challenge() {
         let challenge_input: &str = include_str!("../../docs/challenge_2.txt");
-        let stones: Vec<u64> = challenge_input
-            .split_whitespace()
-            .map(|num| num.parse::<u64>().unwrap())
-            .collect();
+        let stones = parse_stones(challenge_input);
 
-        assert_eq!(calculate_total_stones(&stones, 75), 248967696501656);
+        assert_eq!(
+            calculate_total_stones(&stones, RuleSet::default(), 75),
+            Ok("248967696501656".to_string())
+        );
     }
 }
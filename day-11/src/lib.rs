@@ -0,0 +1,701 @@
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::ops::{Add, AddAssign};
+
+/// Each limb of a [`BigCount`] holds 18 decimal digits.
+const LIMB_BASE: u64 = 1_000_000_000_000_000_000;
+
+/// Unsigned integer of arbitrary size, used for stone counts that overflow `u64` after a
+/// few hundred blinks. Limbs are stored least significant first in base 10^18 so printing
+/// stays trivial.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct BigCount {
+    limbs: Vec<u64>,
+}
+
+impl BigCount {
+    /// Returns zero.
+    pub fn zero() -> Self {
+        BigCount::default()
+    }
+
+    /// Returns one.
+    pub fn one() -> Self {
+        BigCount::from(1)
+    }
+
+    /// Checks whether the count is zero.
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    /// Converts the count to a `u128`, or `None` if it does not fit.
+    pub fn to_u128(&self) -> Option<u128> {
+        self.limbs.iter().rev().try_fold(0u128, |value, &limb| {
+            value
+                .checked_mul(LIMB_BASE as u128)?
+                .checked_add(limb as u128)
+        })
+    }
+
+    /// Number of decimal digits, with zero having one.
+    pub fn digits(&self) -> usize {
+        match self.limbs.last() {
+            None => 1,
+            Some(most) => (self.limbs.len() - 1) * 18 + most.to_string().len(),
+        }
+    }
+}
+
+impl From<u64> for BigCount {
+    fn from(mut value: u64) -> Self {
+        let mut limbs = Vec::new();
+        while value > 0 {
+            limbs.push(value % LIMB_BASE);
+            value /= LIMB_BASE;
+        }
+        BigCount { limbs }
+    }
+}
+
+impl AddAssign<&BigCount> for BigCount {
+    fn add_assign(&mut self, other: &BigCount) {
+        if self.limbs.len() < other.limbs.len() {
+            self.limbs.resize(other.limbs.len(), 0);
+        }
+
+        let mut carry = 0;
+        for (index, limb) in self.limbs.iter_mut().enumerate() {
+            let sum = *limb + other.limbs.get(index).copied().unwrap_or(0) + carry;
+            *limb = sum % LIMB_BASE;
+            carry = sum / LIMB_BASE;
+            if carry == 0 && index >= other.limbs.len() {
+                break;
+            }
+        }
+        if carry > 0 {
+            self.limbs.push(carry);
+        }
+    }
+}
+
+impl Add<&BigCount> for BigCount {
+    type Output = BigCount;
+
+    fn add(mut self, other: &BigCount) -> BigCount {
+        self += other;
+        self
+    }
+}
+
+impl<'a> std::iter::Sum<&'a BigCount> for BigCount {
+    fn sum<I: Iterator<Item = &'a BigCount>>(iter: I) -> Self {
+        iter.fold(BigCount::zero(), |total, count| total + count)
+    }
+}
+
+impl fmt::Display for BigCount {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.limbs.split_last() {
+            None => write!(f, "0"),
+            Some((most, rest)) => {
+                write!(f, "{}", most)?;
+                for limb in rest.iter().rev() {
+                    write!(f, "{:018}", limb)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Parses the puzzle input: stone numbers separated by whitespace.
+pub fn parse_stones(input: &str) -> Vec<u64> {
+    input
+        .split_whitespace()
+        .map(|num| num.parse::<u64>().unwrap())
+        .collect()
+}
+
+/// Number of digits of a stone written in `base`, with zero having one.
+pub fn digit_count(stone: u64, base: u64) -> u32 {
+    stone.checked_ilog(base).unwrap_or(0) + 1
+}
+
+/// Why a blink could not be carried out.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BlinkError {
+    /// A rule turned a stone into a number that does not fit in a `u64`.
+    Overflow { stone: u64 },
+    /// More distinct stones are reachable than the limit allows.
+    Unbounded { limit: usize },
+}
+
+impl fmt::Display for BlinkError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BlinkError::Overflow { stone } => {
+                write!(f, "stone {stone} turns into a number that overflows")
+            }
+            BlinkError::Unbounded { limit } => {
+                write!(f, "more than {limit} distinct stones are reachable")
+            }
+        }
+    }
+}
+
+/// One way a stone can change when blinked at. Rules are tried in order and the first
+/// one that applies decides what the stone becomes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StoneRule {
+    /// A stone engraved with `from` becomes a stone engraved with `to`.
+    Replace { from: u64, to: u64 },
+    /// A stone whose digit count is a multiple of `parts` splits into that many stones,
+    /// each taking an equal share of the digits, most significant first.
+    Split { parts: u32 },
+    /// Any stone is replaced by itself times `factor`.
+    Multiply { factor: u64 },
+}
+
+impl StoneRule {
+    /// Stones that replace `stone`, or `None` if the rule does not apply to it.
+    pub fn apply(&self, stone: u64, base: u64) -> Result<Option<Vec<u64>>, BlinkError> {
+        Ok(match *self {
+            StoneRule::Replace { from, to } => (stone == from).then(|| vec![to]),
+            StoneRule::Split { parts } => {
+                let digits = digit_count(stone, base);
+                digits.is_multiple_of(parts).then(|| {
+                    let share = base.pow(digits / parts);
+                    let mut rest = stone;
+                    let mut stones: Vec<u64> = (0..parts)
+                        .map(|_| {
+                            let part = rest % share;
+                            rest /= share;
+                            part
+                        })
+                        .collect();
+                    stones.reverse();
+                    stones
+                })
+            }
+            StoneRule::Multiply { factor } => Some(vec![
+                stone
+                    .checked_mul(factor)
+                    .ok_or(BlinkError::Overflow { stone })?,
+            ]),
+        })
+    }
+}
+
+impl fmt::Display for StoneRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StoneRule::Replace { from, to } => write!(f, "{from} -> {to}"),
+            StoneRule::Split { parts } => write!(f, "split {parts}"),
+            StoneRule::Multiply { factor } => write!(f, "multiply {factor}"),
+        }
+    }
+}
+
+/// Error found while reading a rule configuration, with its 1-based line number.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ConfigError {
+    /// The line is not a rule nor a `base` setting.
+    InvalidLine { line: usize, text: String },
+    /// The line is well formed but its number cannot be used.
+    InvalidValue { line: usize, reason: &'static str },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigError::InvalidLine { line, text } => {
+                write!(f, "line {line}: unrecognised rule '{text}'")
+            }
+            ConfigError::InvalidValue { line, reason } => write!(f, "line {line}: {reason}"),
+        }
+    }
+}
+
+/// Ordered list of rules, together with the base digits are counted in. A stone that no
+/// rule applies to stays as it is.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RuleSet {
+    pub base: u64,
+    pub rules: Vec<StoneRule>,
+}
+
+impl Default for RuleSet {
+    /// The puzzle's rules: 0 becomes 1, even digit counts split in two, otherwise x2024.
+    fn default() -> Self {
+        RuleSet {
+            base: 10,
+            rules: vec![
+                StoneRule::Replace { from: 0, to: 1 },
+                StoneRule::Split { parts: 2 },
+                StoneRule::Multiply { factor: 2024 },
+            ],
+        }
+    }
+}
+
+impl RuleSet {
+    /// Reads a rule configuration, one rule per line in the order they are tried:
+    ///
+    /// ```text
+    /// # The puzzle's rules
+    /// base 10
+    /// 0 -> 1
+    /// split 2
+    /// multiply 2024
+    /// ```
+    ///
+    /// Blank lines and `#` comments are ignored, and the base defaults to 10.
+    pub fn parse(config: &str) -> Result<Self, ConfigError> {
+        let mut rule_set = RuleSet {
+            base: 10,
+            rules: Vec::new(),
+        };
+
+        for (index, raw) in config.lines().enumerate() {
+            let line = index + 1;
+            let text = raw.split('#').next().unwrap().trim();
+            if text.is_empty() {
+                continue;
+            }
+
+            let invalid = || ConfigError::InvalidLine {
+                line,
+                text: text.to_string(),
+            };
+            let number = |value: &str| value.trim().parse::<u64>().map_err(|_| invalid());
+
+            if let Some((from, to)) = text.split_once("->") {
+                rule_set.rules.push(StoneRule::Replace {
+                    from: number(from)?,
+                    to: number(to)?,
+                });
+                continue;
+            }
+
+            let (keyword, value) = text.split_once(char::is_whitespace).ok_or_else(invalid)?;
+            let value = number(value)?;
+            match keyword {
+                "base" if value < 2 => {
+                    return Err(ConfigError::InvalidValue {
+                        line,
+                        reason: "the base must be at least 2",
+                    });
+                }
+                "base" => rule_set.base = value,
+                "split" if !(2..=u32::MAX as u64).contains(&value) => {
+                    return Err(ConfigError::InvalidValue {
+                        line,
+                        reason: "a stone must split into at least 2 parts",
+                    });
+                }
+                "split" => rule_set.rules.push(StoneRule::Split {
+                    parts: value as u32,
+                }),
+                "multiply" => rule_set.rules.push(StoneRule::Multiply { factor: value }),
+                _ => return Err(invalid()),
+            }
+        }
+
+        Ok(rule_set)
+    }
+
+    /// Stones that replace `stone` after one blink.
+    pub fn blink(&self, stone: u64) -> Result<Vec<u64>, BlinkError> {
+        for rule in &self.rules {
+            if let Some(stones) = rule.apply(stone, self.base)? {
+                return Ok(stones);
+            }
+        }
+        Ok(vec![stone])
+    }
+}
+
+impl fmt::Display for RuleSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "base {}", self.base)?;
+        for rule in &self.rules {
+            writeln!(f, "{rule}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Transition graph between stone values: each node is a distinct stone and its edges
+/// lead to the stones it becomes after one blink, once per resulting stone, so a split
+/// into two equal halves gives two parallel edges. Nodes are numbered in the order they
+/// are discovered and their edges are only worked out when first asked for, which also
+/// memoises the rules: each distinct stone is blinked at once.
+#[derive(Clone, Debug, Default)]
+pub struct StoneGraph {
+    rules: RuleSet,
+    stones: Vec<u64>,
+    index: HashMap<u64, usize>,
+    edges: Vec<Option<Vec<usize>>>,
+}
+
+impl StoneGraph {
+    /// Creates an empty graph for a set of rules.
+    pub fn new(rules: RuleSet) -> Self {
+        StoneGraph {
+            rules,
+            ..StoneGraph::default()
+        }
+    }
+
+    /// Builds the closed set of stones reachable from `initial`, with every edge. Some rule
+    /// sets keep producing new stones, so this gives up past `limit` distinct stones.
+    pub fn closure(initial: &[u64], rules: RuleSet, limit: usize) -> Result<Self, BlinkError> {
+        let mut graph = StoneGraph::new(rules);
+        let mut queue: VecDeque<usize> = initial.iter().map(|&s| graph.insert(s)).collect();
+        while let Some(node) = queue.pop_front() {
+            if graph.edges[node].is_some() {
+                continue;
+            }
+            queue.extend(graph.children(node)?.to_vec());
+            if graph.len() > limit {
+                return Err(BlinkError::Unbounded { limit });
+            }
+        }
+        Ok(graph)
+    }
+
+    /// Rules the stones follow.
+    pub fn rules(&self) -> &RuleSet {
+        &self.rules
+    }
+
+    /// Node of a stone, adding it without edges if it is new.
+    pub fn insert(&mut self, stone: u64) -> usize {
+        *self.index.entry(stone).or_insert_with(|| {
+            self.stones.push(stone);
+            self.edges.push(None);
+            self.stones.len() - 1
+        })
+    }
+
+    /// Node of a stone, if it has been discovered.
+    pub fn node(&self, stone: u64) -> Option<usize> {
+        self.index.get(&stone).copied()
+    }
+
+    /// Stone value of a node.
+    pub fn stone(&self, node: usize) -> u64 {
+        self.stones[node]
+    }
+
+    /// Number of stones discovered so far.
+    pub fn len(&self) -> usize {
+        self.stones.len()
+    }
+
+    /// Checks whether no stone has been discovered.
+    pub fn is_empty(&self) -> bool {
+        self.stones.is_empty()
+    }
+
+    /// Nodes a node turns into after one blink, blinking its stone the first time.
+    pub fn children(&mut self, node: usize) -> Result<&[usize], BlinkError> {
+        if self.edges[node].is_none() {
+            let children = self
+                .rules
+                .blink(self.stones[node])?
+                .into_iter()
+                .map(|stone| self.insert(stone))
+                .collect();
+            self.edges[node] = Some(children);
+        }
+        Ok(self.edges[node].as_deref().unwrap())
+    }
+
+    /// Every edge worked out so far as `(stone, next stone)` pairs, in node order.
+    pub fn transitions(&self) -> impl Iterator<Item = (u64, u64)> + '_ {
+        self.edges
+            .iter()
+            .enumerate()
+            .flat_map(move |(node, edges)| {
+                edges
+                    .iter()
+                    .flatten()
+                    .map(move |&child| (self.stones[node], self.stones[child]))
+            })
+    }
+
+    /// Exports the graph in Graphviz DOT format, one node per stone.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph stones {\n");
+        for stone in &self.stones {
+            dot.push_str(&format!("    \"{stone}\";\n"));
+        }
+        for (from, to) in self.transitions() {
+            dot.push_str(&format!("    \"{from}\" -> \"{to}\";\n"));
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// Stone counts evolved one blink at a time. Stones with the same number behave the same,
+/// so only how many of each there are is tracked, and a blink moves every count along
+/// the transition graph.
+#[derive(Clone, Debug)]
+pub struct Evolution {
+    graph: StoneGraph,
+    counts: Vec<BigCount>,
+    blinks: usize,
+}
+
+impl Evolution {
+    /// Starts from the arrangement of stones in the input, under a set of rules.
+    pub fn new(stones: &[u64], rules: RuleSet) -> Self {
+        let mut graph = StoneGraph::new(rules);
+        let mut counts = Vec::new();
+        for &stone in stones {
+            let node = graph.insert(stone);
+            counts.resize(graph.len(), BigCount::zero());
+            counts[node] += &BigCount::one();
+        }
+        Evolution {
+            graph,
+            counts,
+            blinks: 0,
+        }
+    }
+
+    /// Applies one blink to every stone.
+    pub fn step(&mut self) -> Result<(), BlinkError> {
+        let mut next = vec![BigCount::zero(); self.graph.len()];
+        for node in 0..self.counts.len() {
+            if self.counts[node].is_zero() {
+                continue;
+            }
+            for &child in self.graph.children(node)? {
+                if child >= next.len() {
+                    next.resize(child + 1, BigCount::zero());
+                }
+                next[child] += &self.counts[node];
+            }
+        }
+        self.counts = next;
+        self.blinks += 1;
+        Ok(())
+    }
+
+    /// Blinks until `blinks` blinks have happened in total.
+    pub fn advance_to(&mut self, blinks: usize) -> Result<(), BlinkError> {
+        while self.blinks < blinks {
+            self.step()?;
+        }
+        Ok(())
+    }
+
+    /// Number of blinks so far.
+    pub fn blinks(&self) -> usize {
+        self.blinks
+    }
+
+    /// Total number of stones.
+    pub fn total(&self) -> BigCount {
+        self.counts.iter().sum()
+    }
+
+    /// How many stones carry each number.
+    pub fn counts(&self) -> HashMap<u64, BigCount> {
+        self.counts
+            .iter()
+            .enumerate()
+            .filter(|(_, count)| !count.is_zero())
+            .map(|(node, count)| (self.graph.stone(node), count.clone()))
+            .collect()
+    }
+
+    /// Transition graph of the stones seen so far.
+    pub fn graph(&self) -> &StoneGraph {
+        &self.graph
+    }
+}
+
+/// Total number of stones after a given number of blinks.
+pub fn count_stones(
+    stones: &[u64],
+    rules: &RuleSet,
+    blinks: usize,
+) -> Result<BigCount, BlinkError> {
+    let mut evolution = Evolution::new(stones, rules.clone());
+    evolution.advance_to(blinks)?;
+    Ok(evolution.total())
+}
+
+/// Long-run factor by which the number of stones grows per blink, estimated by evolving
+/// normalised floating point counts over `blinks` blinks. The ratio is averaged over the
+/// last blinks so that a graph whose cycles have different lengths does not make it
+/// oscillate.
+pub fn growth_rate(stones: &[u64], rules: &RuleSet, blinks: usize) -> Result<f64, BlinkError> {
+    const WINDOW: usize = 16;
+
+    let mut graph = StoneGraph::new(rules.clone());
+    let mut counts = vec![0.0; 0];
+    for &stone in stones {
+        let node = graph.insert(stone);
+        counts.resize(graph.len(), 0.0);
+        counts[node] += 1.0;
+    }
+
+    let mut log_ratios = VecDeque::new();
+    for _ in 0..blinks {
+        let total: f64 = counts.iter().sum();
+        let mut next = vec![0.0; graph.len()];
+        for (node, &count) in counts.iter().enumerate() {
+            if count == 0.0 {
+                continue;
+            }
+            for &child in graph.children(node)? {
+                if child >= next.len() {
+                    next.resize(child + 1, 0.0);
+                }
+                next[child] += count / total;
+            }
+        }
+        log_ratios.push_back(next.iter().sum::<f64>().ln());
+        if log_ratios.len() > WINDOW {
+            log_ratios.pop_front();
+        }
+        counts = next;
+    }
+
+    if log_ratios.is_empty() {
+        return Ok(1.0);
+    }
+    Ok((log_ratios.iter().sum::<f64>() / log_ratios.len() as f64).exp())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_big_count() {
+        let big = BigCount::from(u64::MAX);
+        assert_eq!((big.clone() + &big).to_string(), "36893488147419103230");
+        assert_eq!((big.clone() + &big).digits(), 20);
+        assert_eq!(BigCount::zero().digits(), 1);
+        assert_eq!((big.clone() + &big).to_u128(), Some(2 * u64::MAX as u128));
+    }
+
+    #[test]
+    fn test_blink() {
+        let rules = RuleSet::default();
+        assert_eq!(rules.blink(0), Ok(vec![1]));
+        assert_eq!(rules.blink(1000), Ok(vec![10, 0]));
+        assert_eq!(rules.blink(99), Ok(vec![9, 9]));
+        assert_eq!(rules.blink(125), Ok(vec![253000]));
+        assert_eq!(
+            rules.blink(u64::MAX / 1000),
+            Err(BlinkError::Overflow {
+                stone: u64::MAX / 1000
+            })
+        );
+        assert_eq!(digit_count(0, 10), 1);
+        assert_eq!(digit_count(1_000_000, 10), 7);
+        assert_eq!(digit_count(8, 2), 4);
+    }
+
+    #[test]
+    fn test_rule_config() {
+        let config = "# The puzzle's rules\nbase 10\n0 -> 1\n\nsplit 2\nmultiply 2024 # fallback\n";
+        let rules = RuleSet::parse(config).unwrap();
+        assert_eq!(rules, RuleSet::default());
+        assert_eq!(RuleSet::parse(&rules.to_string()), Ok(rules));
+
+        assert_eq!(
+            RuleSet::parse("0 -> 1\nsquare 2"),
+            Err(ConfigError::InvalidLine {
+                line: 2,
+                text: "square 2".to_string()
+            })
+        );
+        assert!(matches!(
+            RuleSet::parse("base 1"),
+            Err(ConfigError::InvalidValue { line: 1, .. })
+        ));
+        assert!(matches!(
+            RuleSet::parse("split 1"),
+            Err(ConfigError::InvalidValue { line: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_rule_variants() {
+        // Split into three, in base 2, leaving stones no rule applies to alone
+        let rules = RuleSet::parse("base 2\nsplit 3").unwrap();
+        assert_eq!(rules.blink(0b101_110_011), Ok(vec![0b101, 0b110, 0b011]));
+        assert_eq!(rules.blink(0b1011), Ok(vec![0b1011]));
+
+        // A different multiplier still counts the same way as blinking stone by stone
+        let rules = RuleSet::parse("0 -> 1\nsplit 2\nmultiply 3").unwrap();
+        let mut stones = vec![125, 17];
+        for _ in 0..12 {
+            stones = stones
+                .iter()
+                .flat_map(|&stone| rules.blink(stone).unwrap())
+                .collect();
+        }
+        assert_eq!(
+            count_stones(&[125, 17], &rules, 12).unwrap().to_u128(),
+            Some(stones.len() as u128)
+        );
+    }
+
+    #[test]
+    fn test_evolution_counts() {
+        let mut evolution = Evolution::new(&[125, 17], RuleSet::default());
+        evolution.advance_to(6).unwrap();
+        assert_eq!(evolution.total().to_u128(), Some(22));
+        assert_eq!(evolution.counts()[&2], BigCount::from(4));
+        assert_eq!(
+            count_stones(&[125, 17], &RuleSet::default(), 25).map(|count| count.to_u128()),
+            Ok(Some(55312))
+        );
+    }
+
+    #[test]
+    fn test_closure() {
+        let mut graph = StoneGraph::closure(&[0], RuleSet::default(), 1000).unwrap();
+        let stones: Vec<u64> = (0..graph.len()).map(|node| graph.stone(node)).collect();
+        assert_eq!(stones[..5], [0, 1, 2024, 20, 24]);
+        assert_eq!(graph.len(), 54);
+
+        // Every edge of the closure stays inside it
+        for node in 0..graph.len() {
+            let before = graph.len();
+            graph.children(node).unwrap();
+            assert_eq!(graph.len(), before);
+        }
+        assert!(graph.transitions().any(|edge| edge == (2024, 20)));
+        assert!(graph.to_dot().contains("\"2024\" -> \"24\";"));
+
+        // Without the split, multiplying never comes back to a stone seen before
+        let rules = RuleSet::parse("0 -> 1\nmultiply 2024").unwrap();
+        assert_eq!(
+            StoneGraph::closure(&[0], rules, 4).unwrap_err(),
+            BlinkError::Unbounded { limit: 4 }
+        );
+    }
+
+    #[test]
+    fn test_thousands_of_blinks() {
+        let rules = RuleSet::default();
+        let count = count_stones(&[125, 17], &rules, 2000).unwrap();
+        let rate = growth_rate(&[125, 17], &rules, 500).unwrap();
+        assert!((1.5..1.6).contains(&rate), "rate {rate}");
+
+        // The number of digits grows by log10(rate) per blink
+        let expected = 2000.0 * rate.log10();
+        assert!((count.digits() as f64 - expected).abs() < 10.0);
+    }
+}
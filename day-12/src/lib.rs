@@ -0,0 +1,566 @@
+/// Movement directions (up, right, down, left).
+const DIRECTIONS: [(i32, i32); 4] = [(0, 1), (1, 0), (0, -1), (-1, 0)];
+
+/// Label of the border of padding around the map.
+const OUTSIDE: u32 = u32::MAX;
+
+/// Label of plots not reached yet while labelling.
+const UNLABELLED: u32 = u32::MAX - 1;
+
+/// Represents the map grid of garden plots, labelled by region.
+/// Plants and labels are stored row by row with a border of padding all around, so that
+/// every plot has four neighbours and the scans below need no bounds checks.
+pub struct GardenPlot {
+    plants: Vec<u8>,    // Plant type of each plot, row by row, padded.
+    rows: usize,        // Number of rows in the grid.
+    cols: usize,        // Number of columns in the grid.
+    labels: Vec<u32>,   // Region label of each plot, row by row, padded.
+    cells: Vec<u32>,    // Plots of every region, as padded indices, grouped by label.
+    starts: Vec<usize>, // Where each region's plots start in `cells`, plus the end.
+}
+
+impl std::fmt::Debug for GardenPlot {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "GardenPlot {{ rows: {}, cols: {}, regions: {} }}",
+            self.rows,
+            self.cols,
+            self.region_count()
+        )
+    }
+}
+
+impl GardenPlot {
+    /// Builds the map from the puzzle input, one row of plant types per line, and labels
+    /// its regions.
+    pub fn parse(input: &str) -> Self {
+        let lines: Vec<&[u8]> = input
+            .lines()
+            .map(|line| line.trim().as_bytes())
+            .filter(|line| !line.is_empty())
+            .collect();
+        let rows = lines.len();
+        let cols = lines.first().map_or(0, |line| line.len());
+        GardenPlot::from_plants(lines.concat(), rows, cols)
+    }
+
+    /// Labels the regions of a grid of `rows` x `cols` plant types, given row by row.
+    pub fn from_plants(plants: Vec<u8>, rows: usize, cols: usize) -> Self {
+        assert_eq!(plants.len(), rows * cols, "the grid must be rectangular");
+        let stride = cols + 2;
+        let mut padded = vec![0; stride * (rows + 2)];
+        let mut labels = vec![OUTSIDE; stride * (rows + 2)];
+        for (row, line) in plants.chunks(cols.max(1)).enumerate() {
+            let start = (row + 1) * stride + 1;
+            padded[start..start + cols].copy_from_slice(line);
+            labels[start..start + cols].fill(UNLABELLED);
+        }
+
+        let mut map = GardenPlot {
+            plants: padded,
+            rows,
+            cols,
+            labels,
+            cells: Vec::with_capacity(rows * cols),
+            starts: vec![0],
+        };
+        map.label_regions();
+        map
+    }
+
+    /// Offset between vertically adjacent plots in the padded arrays.
+    fn stride(&self) -> usize {
+        self.cols + 2
+    }
+
+    /// Padded index of a plot; -1 and `rows`/`cols` reach the padding.
+    fn index(&self, row: isize, col: isize) -> usize {
+        ((row + 1) as usize) * self.stride() + (col + 1) as usize
+    }
+
+    /// Connected-component labelling: a depth-first flood from every unlabelled plot in
+    /// reading order. Each region's plots are appended to `cells` as they are reached,
+    /// so every plot is visited once and no sets are needed.
+    fn label_regions(&mut self) {
+        let stride = self.stride();
+        let mut stack = Vec::new();
+
+        for start in 0..self.labels.len() {
+            if self.labels[start] != UNLABELLED {
+                continue;
+            }
+
+            let label = (self.starts.len() - 1) as u32;
+            let plant = self.plants[start];
+            self.labels[start] = label;
+            stack.push(start);
+
+            while let Some(cell) = stack.pop() {
+                self.cells.push(cell as u32);
+
+                // Check the four neighbours; the padding is never unlabelled
+                for next in [cell + 1, cell + stride, cell - 1, cell - stride] {
+                    if self.labels[next] == UNLABELLED && self.plants[next] == plant {
+                        self.labels[next] = label;
+                        stack.push(next);
+                    }
+                }
+            }
+
+            self.starts.push(self.cells.len());
+        }
+    }
+
+    /// Number of rows in the grid.
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Number of columns in the grid.
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// Plant type at a cell.
+    pub fn plant(&self, row: usize, col: usize) -> char {
+        self.plants[self.index(row as isize, col as isize)] as char
+    }
+
+    /// Region label of a cell, or `OUTSIDE` past the edges of the map.
+    fn label(&self, row: isize, col: isize) -> u32 {
+        if row < 0 || col < 0 || row as usize >= self.rows || col as usize >= self.cols {
+            OUTSIDE
+        } else {
+            self.labels[self.index(row, col)]
+        }
+    }
+
+    /// Number of regions on the map.
+    pub fn region_count(&self) -> usize {
+        self.starts.len() - 1
+    }
+
+    /// A region by its label; labels count up from 0 in reading order of each region's
+    /// first plot.
+    pub fn region(&self, label: u32) -> Region<'_> {
+        let cells = &self.cells[self.starts[label as usize]..self.starts[label as usize + 1]];
+        Region {
+            map: self,
+            label,
+            plant_type: self.plants[cells[0] as usize] as char,
+            cells,
+        }
+    }
+
+    /// The region holding a plot, if the plot is on the map.
+    pub fn region_at(&self, row: usize, col: usize) -> Option<Region<'_>> {
+        (row < self.rows && col < self.cols)
+            .then(|| self.region(self.labels[self.index(row as isize, col as isize)]))
+    }
+
+    /// Every region of the map, in reading order of their first plot.
+    pub fn regions(&self) -> Vec<Region<'_>> {
+        (0..self.region_count() as u32)
+            .map(|label| self.region(label))
+            .collect()
+    }
+
+    /// Perimeter of every region, indexed by label, from one pass over the plots that
+    /// counts each edge between two different labels.
+    pub fn perimeters(&self) -> Vec<usize> {
+        let stride = self.stride();
+        let mut perimeters = vec![0; self.region_count()];
+        for (cell, &label) in self.labels.iter().enumerate() {
+            if label == OUTSIDE {
+                continue;
+            }
+            let fences = [cell + 1, cell + stride, cell - 1, cell - stride]
+                .iter()
+                .filter(|&&next| self.labels[next] != label)
+                .count();
+            perimeters[label as usize] += fences;
+        }
+        perimeters
+    }
+
+    /// Number of sides of every region, indexed by label, from one pass over the corners
+    /// of the grid. Each grid vertex looks at the four plots around it: a label on one of
+    /// them, or on three, turns there; a label on two diagonal plots turns twice.
+    pub fn sides(&self) -> Vec<usize> {
+        let stride = self.stride();
+        let mut sides = vec![0; self.region_count()];
+        for row in 0..=self.rows {
+            for col in 0..=self.cols {
+                // Clockwise from the top-left plot, so diagonal plots are two apart
+                let top_left = row * stride + col;
+                let around = [
+                    self.labels[top_left],
+                    self.labels[top_left + 1],
+                    self.labels[top_left + stride + 1],
+                    self.labels[top_left + stride],
+                ];
+                if around[1..].iter().all(|&label| label == around[0]) {
+                    continue;
+                }
+                for (i, &label) in around.iter().enumerate() {
+                    // Handle each label once, at its first position around the vertex
+                    if label == OUTSIDE || around[..i].contains(&label) {
+                        continue;
+                    }
+                    let here = around.map(|other| other == label);
+                    sides[label as usize] += match here.iter().filter(|&&h| h).count() {
+                        1 | 3 => 1,
+                        2 if here[0] == here[2] => 2,
+                        _ => 0,
+                    };
+                }
+            }
+        }
+        sides
+    }
+
+    /// Draws the map with only the cells of `region` showing their plant type.
+    pub fn highlight(&self, region: &Region) -> String {
+        let mut picture = String::with_capacity((self.cols + 1) * self.rows);
+        for row in 0..self.rows as isize {
+            for col in 0..self.cols as isize {
+                let cell = self.index(row, col);
+                picture.push(if self.labels[cell] == region.label {
+                    self.plants[cell] as char
+                } else {
+                    '.'
+                });
+            }
+            picture.push('\n');
+        }
+        picture
+    }
+}
+
+/// The smallest rectangle holding a region, as inclusive top-left and bottom-right corners.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BoundingBox {
+    pub top: usize,
+    pub left: usize,
+    pub bottom: usize,
+    pub right: usize,
+}
+
+impl BoundingBox {
+    pub fn width(&self) -> usize {
+        self.right - self.left + 1
+    }
+
+    pub fn height(&self) -> usize {
+        self.bottom - self.top + 1
+    }
+}
+
+/// Represents a region of connected garden plots with the same plant type.
+#[derive(Debug, Clone, Copy)]
+pub struct Region<'a> {
+    map: &'a GardenPlot, // Map the region was labelled on.
+    label: u32,          // Label of the region's plots on the map.
+    plant_type: char,    // Type of plant in this region.
+    cells: &'a [u32],    // Plots of the region, as row-major indices.
+}
+
+impl Region<'_> {
+    /// Label of the region on its map.
+    pub fn label(&self) -> u32 {
+        self.label
+    }
+
+    /// Type of plant in this region.
+    pub fn plant_type(&self) -> char {
+        self.plant_type
+    }
+
+    /// Total number of plots in this region.
+    pub fn area(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Coordinates belonging to this region, as `(row, col)` in the order they were reached.
+    pub fn coordinates(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let stride = self.map.stride();
+        self.cells
+            .iter()
+            .map(move |&cell| (cell as usize / stride - 1, cell as usize % stride - 1))
+    }
+
+    /// Whether a cell belongs to this region; cells off the map never do.
+    pub fn contains(&self, row: isize, col: isize) -> bool {
+        self.map.label(row, col) == self.label
+    }
+
+    /// Number of fence segments: plot edges facing another region or the edge of the map.
+    pub fn perimeter(&self) -> usize {
+        self.coordinates()
+            .map(|(row, col)| {
+                DIRECTIONS
+                    .iter()
+                    .filter(|&&(dr, dc)| {
+                        !self.contains(row as isize + dr as isize, col as isize + dc as isize)
+                    })
+                    .count()
+            })
+            .sum()
+    }
+
+    /// Number of straight fence sides, which equals the number of corners.
+    /// Each plot checks its four diagonal quadrants: it has an outer corner there when
+    /// both orthogonal neighbours are outside the region, and an inner corner when both
+    /// are inside but the diagonal one is not.
+    pub fn sides(&self) -> usize {
+        let mut corners = 0;
+        for (row, col) in self.coordinates() {
+            let (row, col) = (row as isize, col as isize);
+            for (dr, dc) in [(-1, -1), (-1, 1), (1, -1), (1, 1)] {
+                let vertical = self.contains(row + dr, col);
+                let horizontal = self.contains(row, col + dc);
+                let diagonal = self.contains(row + dr, col + dc);
+                if (!vertical && !horizontal) || (vertical && horizontal && !diagonal) {
+                    corners += 1;
+                }
+            }
+        }
+        corners
+    }
+
+    /// The smallest rectangle holding the region.
+    pub fn bounding_box(&self) -> BoundingBox {
+        let (row, col) = self.coordinates().next().unwrap();
+        self.coordinates().fold(
+            BoundingBox {
+                top: row,
+                left: col,
+                bottom: row,
+                right: col,
+            },
+            |bounds, (row, col)| BoundingBox {
+                top: bounds.top.min(row),
+                left: bounds.left.min(col),
+                bottom: bounds.bottom.max(row),
+                right: bounds.right.max(col),
+            },
+        )
+    }
+
+    /// The parts of the map enclosed by this region: groups of connected plots of other
+    /// plants that cannot reach the edge of the map without crossing it.
+    /// Each hole is one group, whatever plants grow in it, in reading order of their
+    /// first plot, with its plots in reading order too.
+    pub fn holes(&self) -> Vec<Vec<(usize, usize)>> {
+        // Only plots inside the bounding box, away from its border, can be enclosed
+        let bounds = self.bounding_box();
+        let (width, height) = (bounds.width(), bounds.height());
+        let inside = |r: usize, c: usize| {
+            self.contains((bounds.top + r) as isize, (bounds.left + c) as isize)
+        };
+        let mut seen = vec![false; width * height];
+        let mut holes = Vec::new();
+        let mut stack = Vec::new();
+
+        for start in 0..width * height {
+            if seen[start] || inside(start / width, start % width) {
+                continue;
+            }
+
+            // Flood the plots outside the region, noting whether they escape the box
+            let mut group = Vec::new();
+            let mut escapes = false;
+            seen[start] = true;
+            stack.push((start / width, start % width));
+            while let Some((r, c)) = stack.pop() {
+                group.push((bounds.top + r, bounds.left + c));
+                for &(dr, dc) in &DIRECTIONS {
+                    let (nr, nc) = (r as isize + dr as isize, c as isize + dc as isize);
+                    if nr < 0 || nc < 0 || nr as usize >= height || nc as usize >= width {
+                        escapes = true;
+                        continue;
+                    }
+                    let (nr, nc) = (nr as usize, nc as usize);
+                    if !seen[nr * width + nc] && !inside(nr, nc) {
+                        seen[nr * width + nc] = true;
+                        stack.push((nr, nc));
+                    }
+                }
+            }
+
+            if !escapes {
+                group.sort_unstable();
+                holes.push(group);
+            }
+        }
+        holes
+    }
+
+    /// Whether every row and every column crosses the region in one unbroken run of
+    /// plots, i.e. the region is orthogonally convex. Regions with holes never are.
+    pub fn is_convex(&self) -> bool {
+        let bounds = self.bounding_box();
+        let runs_unbroken = |cells: &mut dyn Iterator<Item = bool>| {
+            let mut runs = 0;
+            let mut previous = false;
+            for inside in cells {
+                if inside && !previous {
+                    runs += 1;
+                }
+                previous = inside;
+            }
+            runs <= 1
+        };
+
+        (bounds.top..=bounds.bottom).all(|row| {
+            runs_unbroken(
+                &mut (bounds.left..=bounds.right)
+                    .map(|col| self.contains(row as isize, col as isize)),
+            )
+        }) && (bounds.left..=bounds.right).all(|col| {
+            runs_unbroken(
+                &mut (bounds.top..=bounds.bottom)
+                    .map(|row| self.contains(row as isize, col as isize)),
+            )
+        })
+    }
+
+    /// One line summary of the region's shape.
+    pub fn summary(&self) -> String {
+        let bounds = self.bounding_box();
+        format!(
+            "{}: area {}, perimeter {}, sides {}, holes {}, box {}x{} at {},{}, {}",
+            self.plant_type,
+            self.area(),
+            self.perimeter(),
+            self.sides(),
+            self.holes().len(),
+            bounds.width(),
+            bounds.height(),
+            bounds.top,
+            bounds.left,
+            if self.is_convex() {
+                "convex"
+            } else {
+                "not convex"
+            }
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn region_at(map: &GardenPlot, row: usize, col: usize) -> Region<'_> {
+        map.region_at(row, col).unwrap()
+    }
+
+    // A garden of random plants, with a few kinds so regions grow large and twisted
+    fn generated(rows: usize, cols: usize, kinds: u64) -> GardenPlot {
+        let mut state = 0x2545_f491_4f6c_dd1du64;
+        let plants = (0..rows * cols)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                b'A' + (state % kinds) as u8
+            })
+            .collect();
+        GardenPlot::from_plants(plants, rows, cols)
+    }
+
+    #[test]
+    fn test_region_stats() {
+        let map = GardenPlot::parse("AAAA\nBBCD\nBBCC\nEEEC");
+        let c = region_at(&map, 1, 2);
+        assert_eq!((c.plant_type(), c.area()), ('C', 4));
+        assert_eq!((c.perimeter(), c.sides()), (10, 8));
+        assert_eq!(
+            c.bounding_box(),
+            BoundingBox {
+                top: 1,
+                left: 2,
+                bottom: 3,
+                right: 3
+            }
+        );
+        // A staircase still crosses every row and column in one run
+        assert!(c.is_convex());
+        assert!(region_at(&map, 1, 0).is_convex());
+        let u = GardenPlot::parse("ABA\nAAA");
+        assert!(!region_at(&u, 0, 0).is_convex());
+        assert!(region_at(&map, 0, 0).holes().is_empty());
+    }
+
+    #[test]
+    fn test_holes() {
+        let map = GardenPlot::parse("OOOOO\nOXOXO\nOOOOO\nOXOXO\nOOOOO");
+        let o = region_at(&map, 0, 0);
+        assert_eq!(o.holes().len(), 4);
+        assert_eq!((o.area(), o.perimeter(), o.sides()), (21, 36, 20));
+        assert!(!o.is_convex());
+
+        // One hole holding two plants, and a pocket open to the outside
+        let map = GardenPlot::parse("AAAAAA\nABBCAA\nAAAAAD\nAAAADD");
+        let a = region_at(&map, 0, 0);
+        assert_eq!(a.holes(), vec![vec![(1, 1), (1, 2), (1, 3)]]);
+        assert_eq!(region_at(&map, 2, 5).holes().len(), 0);
+    }
+
+    #[test]
+    fn test_highlight() {
+        let map = GardenPlot::parse("AAAA\nBBCD\nBBCC\nEEEC");
+        assert_eq!(
+            map.highlight(&region_at(&map, 1, 2)),
+            "....\n..C.\n..CC\n...C\n"
+        );
+        assert_eq!(
+            region_at(&map, 1, 2).summary(),
+            "C: area 4, perimeter 10, sides 8, holes 0, box 2x3 at 1,2, convex"
+        );
+    }
+
+    #[test]
+    fn test_labels() {
+        let map = GardenPlot::parse("OOOOO\nOXOXO\nOOOOO\nOXOXO\nOOOOO");
+        assert_eq!(map.region_count(), 5);
+        assert_eq!(region_at(&map, 3, 3).label(), 4);
+        assert!(map.region_at(5, 0).is_none());
+        assert_eq!(map.perimeters(), vec![36, 4, 4, 4, 4]);
+        assert_eq!(map.sides(), vec![20, 4, 4, 4, 4]);
+
+        // Plots touching only at a corner are separate regions
+        let map = GardenPlot::parse("AB\nBA");
+        assert_eq!(map.region_count(), 4);
+        assert_eq!(map.sides(), vec![4; 4]);
+    }
+
+    #[test]
+    fn test_map_scans_match_regions() {
+        let map = generated(120, 90, 3);
+        let (perimeters, sides) = (map.perimeters(), map.sides());
+        let regions = map.regions();
+        assert_eq!(regions.iter().map(|r| r.area()).sum::<usize>(), 120 * 90);
+        for region in regions {
+            assert_eq!(perimeters[region.label() as usize], region.perimeter());
+            assert_eq!(sides[region.label() as usize], region.sides());
+            assert!(
+                region
+                    .coordinates()
+                    .all(|(row, col)| map.plant(row, col) == region.plant_type())
+            );
+        }
+    }
+
+    #[test]
+    fn test_large_garden() {
+        let map = generated(2000, 2000, 4);
+        let perimeters = map.perimeters();
+        let sides = map.sides();
+        let total_area: usize = map.regions().iter().map(|r| r.area()).sum();
+        assert_eq!(total_area, 4_000_000);
+        assert!(sides.iter().zip(&perimeters).all(|(s, p)| s <= p));
+    }
+}
@@ -0,0 +1,1673 @@
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Offset added to both prize coordinates once the unit conversion error is fixed in part 2.
+pub const PART_2_OFFSET: i128 = 10_000_000_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClawMachine {
+    pub a: (i128, i128),     // Coordinates for button A's movement vector
+    pub b: (i128, i128),     // Coordinates for button B's movement vector
+    pub prize: (i128, i128), // Coordinates of the prize
+}
+
+/// Tokens spent on one press of each button.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Costs {
+    pub a: u64,
+    pub b: u64,
+}
+
+impl Default for Costs {
+    fn default() -> Self {
+        Costs { a: 3, b: 1 }
+    }
+}
+
+/// The cheapest way to win a machine's prize.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Solution {
+    pub a: i128,    // Presses of button A
+    pub b: i128,    // Presses of button B
+    pub cost: i128, // Tokens spent
+}
+
+/// A fraction in lowest terms with a positive denominator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fraction {
+    pub numerator: i128,
+    pub denominator: i128,
+}
+
+impl Fraction {
+    pub fn new(numerator: i128, denominator: i128) -> Self {
+        assert!(denominator != 0, "fraction with a zero denominator");
+        let g = gcd(numerator, denominator).max(1) * denominator.signum();
+        Fraction {
+            numerator: numerator / g,
+            denominator: denominator / g,
+        }
+    }
+}
+
+impl fmt::Display for Fraction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.denominator == 1 {
+            write!(f, "{}", self.numerator)
+        } else {
+            write!(f, "{}/{}", self.numerator, self.denominator)
+        }
+    }
+}
+
+/// Why no sequence of button presses puts the claw exactly over the prize.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unwinnable {
+    /// The buttons move in different directions and the only way to the prize takes
+    /// a fractional number of presses.
+    FractionalPresses { a: Fraction, b: Fraction },
+    /// The buttons move in different directions and the only way to the prize takes
+    /// a negative number of presses.
+    NegativePresses { a: i128, b: i128 },
+    /// Neither button moves the claw, and the prize is not where the claw starts.
+    Stuck,
+    /// Both buttons move along the same line and the prize is not on it.
+    OffLine,
+    /// Both buttons move along the same line by whole multiples of `gcd` steps, and the
+    /// prize is on that line `distance` steps away, which those moves cannot add up to.
+    Indivisible { gcd: i128, distance: i128 },
+    /// Both buttons move along the same line and every way to add up to the prize
+    /// presses some button a negative number of times.
+    NoNonNegative,
+    /// No combination of the buttons, even with fractional presses, reaches the prize.
+    OutOfSpan,
+    /// The prize is only reached with a fractional number of presses.
+    OffLattice,
+    /// Every way to the prize presses some button a negative number of times, or more
+    /// times than the press limit.
+    Infeasible,
+    /// Some buttons cancel each other out at no cost, so they can be pressed more and more
+    /// forever; a press limit is needed to end the search.
+    Unbounded,
+}
+
+impl fmt::Display for Unwinnable {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Unwinnable::FractionalPresses { a, b } => write!(
+                f,
+                "the only way to the prize takes a fractional number of presses: {} of A and {} of B",
+                a, b
+            ),
+            Unwinnable::NegativePresses { a, b } => write!(
+                f,
+                "the only way to the prize takes a negative number of presses: {} of A and {} of B",
+                a, b
+            ),
+            Unwinnable::Stuck => write!(f, "neither button moves the claw"),
+            Unwinnable::OffLine => write!(
+                f,
+                "both buttons move along the same line and the prize is not on it"
+            ),
+            Unwinnable::Indivisible { gcd, distance } => write!(
+                f,
+                "both buttons move along the same line in multiples of {} steps, which never add up to the prize's {} steps",
+                gcd, distance
+            ),
+            Unwinnable::NoNonNegative => write!(
+                f,
+                "both buttons move along the same line and every way to the prize needs negative presses"
+            ),
+            Unwinnable::OutOfSpan => {
+                write!(f, "no combination of the buttons points towards the prize")
+            }
+            Unwinnable::OffLattice => write!(
+                f,
+                "the buttons only reach the prize with a fractional number of presses"
+            ),
+            Unwinnable::Infeasible => write!(
+                f,
+                "every way to the prize needs negative presses or goes over the press limit"
+            ),
+            Unwinnable::Unbounded => write!(
+                f,
+                "some buttons cancel out for free and can be pressed forever; set a press limit"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Unwinnable {}
+
+/// Greatest common divisor, always non-negative.
+fn gcd(a: i128, b: i128) -> i128 {
+    let (mut a, mut b) = (a.abs(), b.abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Extended Euclid: returns `(g, x, y)` with `a * x + b * y = g = gcd(a, b) >= 0`.
+fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+        (old_y, y) = (y, old_y - q * y);
+    }
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+impl ClawMachine {
+    /// The same machine with the prize moved by `(dx, dy)`.
+    pub fn with_offset(&self, (dx, dy): (i128, i128)) -> Self {
+        ClawMachine {
+            prize: (self.prize.0 + dx, self.prize.1 + dy),
+            ..*self
+        }
+    }
+
+    /// Find the cheapest non-negative presses of A and B that reach the prize exactly,
+    /// or explain why there are none.
+    pub fn solve(&self, costs: Costs) -> Result<Solution, Unwinnable> {
+        let (a, b, prize) = (self.a, self.b, self.prize);
+        let det = a.0 * b.1 - a.1 * b.0;
+        if det == 0 {
+            return self.solve_collinear(costs);
+        }
+
+        // The buttons span the plane, so Cramer's rule gives the only solution
+        let n = Fraction::new(b.1 * prize.0 - b.0 * prize.1, det);
+        let m = Fraction::new(a.0 * prize.1 - a.1 * prize.0, det);
+        if n.denominator != 1 || m.denominator != 1 {
+            return Err(Unwinnable::FractionalPresses { a: n, b: m });
+        }
+        let (n, m) = (n.numerator, m.numerator);
+        if n < 0 || m < 0 {
+            return Err(Unwinnable::NegativePresses { a: n, b: m });
+        }
+        Ok(Solution {
+            a: n,
+            b: m,
+            cost: n * costs.a as i128 + m * costs.b as i128,
+        })
+    }
+
+    /// Solve a machine whose buttons move along one line.
+    /// Writing both buttons and the prize as multiples `alpha`, `beta` and `pi` of the
+    /// smallest step along that line leaves `alpha * n + beta * m = pi`. Its integer
+    /// solutions form the family `n = n0 + k * beta / g`, `m = m0 - k * alpha / g` from the
+    /// extended gcd, on which the cost is linear in `k`, so the cheapest one sits at an end
+    /// of the range of `k` where both counts are non-negative.
+    fn solve_collinear(&self, costs: Costs) -> Result<Solution, Unwinnable> {
+        let (a, b, prize) = (self.a, self.b, self.prize);
+        let Some(&direction) = [a, b].iter().find(|&&v| v != (0, 0)) else {
+            return if prize == (0, 0) {
+                Ok(Solution {
+                    a: 0,
+                    b: 0,
+                    cost: 0,
+                })
+            } else {
+                Err(Unwinnable::Stuck)
+            };
+        };
+
+        // Smallest whole step along the line, and how many of them each vector is
+        let unit = gcd(direction.0, direction.1);
+        let step = (direction.0 / unit, direction.1 / unit);
+        let along = |v: (i128, i128)| -> Option<i128> {
+            if v.0 * step.1 != v.1 * step.0 {
+                None
+            } else if step.0 != 0 {
+                (v.0 % step.0 == 0).then(|| v.0 / step.0)
+            } else {
+                (v.1 % step.1 == 0).then(|| v.1 / step.1)
+            }
+        };
+        let (alpha, beta) = (along(a).unwrap(), along(b).unwrap());
+        let pi = along(prize).ok_or(Unwinnable::OffLine)?;
+
+        let (g, x, y) = extended_gcd(alpha, beta);
+        if pi % g != 0 {
+            return Err(Unwinnable::Indivisible {
+                gcd: g,
+                distance: pi,
+            });
+        }
+        let (n0, m0) = (x * (pi / g), y * (pi / g));
+        let (dn, dm) = (beta / g, -alpha / g);
+
+        // Range of k keeping `start + k * slope` non-negative for both counts
+        let (mut low, mut high) = (None::<i128>, None::<i128>);
+        for (start, slope) in [(n0, dn), (m0, dm)] {
+            match slope.signum() {
+                1 => {
+                    let bound =
+                        (-start).div_euclid(slope) + ((-start).rem_euclid(slope) != 0) as i128;
+                    low = Some(low.map_or(bound, |low| low.max(bound)));
+                }
+                -1 => {
+                    let bound = start.div_euclid(-slope);
+                    high = Some(high.map_or(bound, |high| high.min(bound)));
+                }
+                _ if start < 0 => return Err(Unwinnable::NoNonNegative),
+                _ => {}
+            }
+        }
+        if let (Some(low), Some(high)) = (low, high)
+            && low > high
+        {
+            return Err(Unwinnable::NoNonNegative);
+        }
+
+        // The cost only falls in a direction where both counts shrink, which is bounded
+        let cost_slope = dn * costs.a as i128 + dm * costs.b as i128;
+        let k = if cost_slope < 0 {
+            high.or(low)
+        } else {
+            low.or(high)
+        }
+        .unwrap_or(0);
+        let (n, m) = (n0 + k * dn, m0 + k * dm);
+        Ok(Solution {
+            a: n,
+            b: m,
+            cost: n * costs.a as i128 + m * costs.b as i128,
+        })
+    }
+}
+
+/// Parse the input string into a vector of ClawMachine structs.
+/// Offsets may carry either sign, as in `X+94` or `X-3`.
+pub fn parse(input: &str) -> Vec<ClawMachine> {
+    // Regex pattern to match the input format
+    let pattern = r"Button A: X([+-]\d+), Y([+-]\d+)\s*Button B: X([+-]\d+), Y([+-]\d+)\s*Prize: X=(-?\d+), Y=(-?\d+)";
+    let rx = Regex::new(pattern).unwrap(); // Compile the regex
+    let int = |s: &str| s.parse::<i128>().unwrap();
+
+    rx.captures_iter(input) // Iterate over all matches in the input string
+        .map(|c| ClawMachine {
+            a: (int(&c[1]), int(&c[2])),
+            b: (int(&c[3]), int(&c[4])),
+            prize: (int(&c[5]), int(&c[6])),
+        })
+        .collect()
+}
+
+/// A button of an extended machine: how far one press moves the claw along each axis,
+/// and its token cost when the input gives one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Button {
+    pub name: String,
+    pub moves: Vec<i128>,
+    pub cost: Option<u64>,
+}
+
+/// A claw machine with any number of buttons moving the claw along any number of axes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Machine {
+    pub axes: Vec<char>,
+    pub buttons: Vec<Button>,
+    pub prize: Vec<i128>,
+}
+
+/// The cheapest presses of every button of an extended machine.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Presses {
+    pub presses: Vec<i128>, // Presses of each button, in input order
+    pub cost: i128,         // Tokens spent
+}
+
+impl From<&ClawMachine> for Machine {
+    fn from(cm: &ClawMachine) -> Self {
+        let button = |name: &str, (x, y): (i128, i128)| Button {
+            name: name.to_string(),
+            moves: vec![x, y],
+            cost: None,
+        };
+        Machine {
+            axes: vec!['X', 'Y'],
+            buttons: vec![button("A", cm.a), button("B", cm.b)],
+            prize: vec![cm.prize.0, cm.prize.1],
+        }
+    }
+}
+
+/// Hermite normal form by unimodular column operations: returns `(h, u, pivots)` with
+/// `h = matrix * u` in lower column echelon form, where column `j < pivots.len()` has its
+/// first non-zero entry, which is positive, on row `pivots[j]`, and every later column is zero.
+/// The last `columns - pivots.len()` columns of `u` therefore span the integer kernel.
+fn hermite(matrix: &[Vec<i128>], columns: usize) -> (Vec<Vec<i128>>, Vec<Vec<i128>>, Vec<usize>) {
+    let mut h = matrix.to_vec();
+    let mut u: Vec<Vec<i128>> = (0..columns)
+        .map(|i| (0..columns).map(|j| (i == j) as i128).collect())
+        .collect();
+    let mut pivots = Vec::new();
+
+    // Apply one column operation to both matrices
+    let mut update = |h: &mut Vec<Vec<i128>>, op: &dyn Fn(&mut Vec<i128>)| {
+        h.iter_mut().for_each(op);
+        u.iter_mut().for_each(op);
+    };
+
+    for row in 0..h.len() {
+        let rank = pivots.len();
+        if rank == columns {
+            break;
+        }
+        // Euclid's algorithm across the row until a single non-zero entry is left
+        while let Some(p) = (rank..columns)
+            .filter(|&c| h[row][c] != 0)
+            .min_by_key(|&c| h[row][c].abs())
+        {
+            let others: Vec<usize> = (rank..columns)
+                .filter(|&c| c != p && h[row][c] != 0)
+                .collect();
+            if others.is_empty() {
+                let sign = h[row][p].signum();
+                update(&mut h, &|r: &mut Vec<i128>| {
+                    r.swap(p, rank);
+                    r[rank] *= sign;
+                });
+                pivots.push(row);
+                break;
+            }
+            for c in others {
+                let q = h[row][c] / h[row][p];
+                update(&mut h, &|r: &mut Vec<i128>| r[c] -= q * r[p]);
+            }
+        }
+    }
+    (h, u, pivots)
+}
+
+impl Machine {
+    /// Cost of each button: its own cost if the input gave one, otherwise the default
+    /// for A and B and 1 token for any other button.
+    pub fn button_costs(&self, defaults: Costs) -> Vec<u64> {
+        self.buttons
+            .iter()
+            .map(|button| {
+                button.cost.unwrap_or(match button.name.as_str() {
+                    "A" => defaults.a,
+                    "B" => defaults.b,
+                    _ => 1,
+                })
+            })
+            .collect()
+    }
+
+    /// The same machine with the prize moved by `offset`: one value per axis, or a single
+    /// value for every axis.
+    pub fn with_offset(&self, offset: &[i128]) -> Self {
+        let prize = self
+            .prize
+            .iter()
+            .enumerate()
+            .map(|(i, p)| match offset {
+                [all] => p + all,
+                _ => p + offset.get(i).copied().unwrap_or(0),
+            })
+            .collect();
+        Machine {
+            prize,
+            ..self.clone()
+        }
+    }
+
+    /// The two-button, two-axis machine this is, if it is one.
+    pub fn as_claw_machine(&self) -> Option<ClawMachine> {
+        match (self.axes.as_slice(), self.buttons.as_slice()) {
+            ([_, _], [a, b]) => Some(ClawMachine {
+                a: (a.moves[0], a.moves[1]),
+                b: (b.moves[0], b.moves[1]),
+                prize: (self.prize[0], self.prize[1]),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Find the cheapest presses that reach the prize exactly, pressing no button more
+    /// than `limit` times if given, or explain why there are none.
+    /// Two-button machines on two axes without a limit keep going through
+    /// `ClawMachine::solve`, and so keep its explanations; the rest use `solve_lattice`.
+    pub fn solve(&self, defaults: Costs, limit: Option<i128>) -> Result<Presses, Unwinnable> {
+        let costs = self.button_costs(defaults);
+        match self.as_claw_machine() {
+            Some(cm) if limit.is_none() => {
+                let s = cm.solve(Costs {
+                    a: costs[0],
+                    b: costs[1],
+                })?;
+                Ok(Presses {
+                    presses: vec![s.a, s.b],
+                    cost: s.cost,
+                })
+            }
+            _ => self.solve_lattice(&costs, limit),
+        }
+    }
+
+    /// Solve the integer program `min costs . x` subject to `M x = prize`, `0 <= x <= limit`.
+    /// The Hermite normal form of the button matrix `M` gives one integer solution `x0` and
+    /// a basis `K` of the integer kernel, so every solution is `x0 + K z`. Without a limit
+    /// the presses can only grow forever along a non-negative kernel vector; if one costs
+    /// nothing, which is checked for once up front, there is no telling when to stop.
+    /// Otherwise the cost is capped by `proximity` above the cheapest fractional presses,
+    /// which leaves a bounded polytope where `Search` finds the cheapest whole presses by
+    /// branch and bound over `z`, or shows there are none.
+    pub fn solve_lattice(&self, costs: &[u64], limit: Option<i128>) -> Result<Presses, Unwinnable> {
+        let (axes, buttons) = (self.axes.len(), self.buttons.len());
+        let matrix: Vec<Vec<i128>> = (0..axes)
+            .map(|i| self.buttons.iter().map(|b| b.moves[i]).collect())
+            .collect();
+
+        // The prize must lie in the span of the buttons, then on their lattice
+        let (h, u, pivots) = hermite(&matrix, buttons);
+        let augmented: Vec<Vec<i128>> = matrix
+            .iter()
+            .zip(&self.prize)
+            .map(|(row, &p)| row.iter().copied().chain([p]).collect())
+            .collect();
+        if hermite(&augmented, buttons + 1).2.len() > pivots.len() {
+            return Err(Unwinnable::OutOfSpan);
+        }
+        let mut y = Vec::with_capacity(pivots.len());
+        for (j, &row) in pivots.iter().enumerate() {
+            let rest = self.prize[row] - (0..j).map(|l| h[row][l] * y[l]).sum::<i128>();
+            if rest % h[row][j] != 0 {
+                return Err(Unwinnable::OffLattice);
+            }
+            y.push(rest / h[row][j]);
+        }
+        let x0: Vec<i128> = (0..buttons)
+            .map(|i| (0..y.len()).map(|j| u[i][j] * y[j]).sum())
+            .collect();
+        let kernel: Vec<Vec<i128>> = u.iter().map(|row| row[y.len()..].to_vec()).collect();
+        let dimension = buttons - y.len();
+        let (kernel, _, _) = hermite(&kernel, dimension);
+
+        let mut search = Search::new(&x0, &kernel, costs, limit, None);
+        if search.systems[0].is_none() {
+            return Err(Unwinnable::Infeasible);
+        }
+        if limit.is_none() {
+            if has_free_ray(&kernel, costs) {
+                return Err(Unwinnable::Unbounded);
+            }
+            // Presses never cost less than nothing, so the cost has a lowest value
+            let lowest = search.lowest_cost(&[]).ok_or(Unwinnable::Infeasible)?;
+            let ceiling = lowest.saturating_add(proximity(&matrix, costs));
+            search = Search::new(&x0, &kernel, costs, None, Some(ceiling));
+        }
+        let mut best = None;
+        search.run(&mut Vec::with_capacity(dimension), &mut best);
+        best.ok_or(Unwinnable::Infeasible)
+    }
+}
+
+/// A linear inequality `coefficients . v >= constant` over integer variables.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Inequality {
+    coefficients: Vec<i128>,
+    constant: i128,
+}
+
+impl Inequality {
+    /// Divides through by the gcd of the coefficients, rounding the constant up. Only
+    /// fractional points are cut off, so every integer solution is kept.
+    fn normalised(mut self) -> Self {
+        let g = self.coefficients.iter().fold(0, |g, &a| gcd(g, a));
+        if g > 1 {
+            self.coefficients.iter_mut().for_each(|a| *a /= g);
+            self.constant = -(-self.constant).div_euclid(g);
+        }
+        self
+    }
+
+    /// The inequality with variable `var` fixed to `value`.
+    fn substitute(&self, var: usize, value: i128) -> Self {
+        let mut coefficients = self.coefficients.clone();
+        coefficients[var] = 0;
+        Inequality {
+            coefficients,
+            constant: self.constant - self.coefficients[var] * value,
+        }
+    }
+}
+
+/// Fourier-Motzkin elimination of variable `var`: every pair of a lower and an upper bound
+/// on it adds up to an inequality without it. Returns `None` if the system has no solution
+/// left, which shows up as an inequality `0 >= constant` with a positive constant.
+fn eliminate(system: &[Inequality], var: usize) -> Option<Vec<Inequality>> {
+    let (mut lower, mut upper, mut kept) = (Vec::new(), Vec::new(), Vec::new());
+    for inequality in system {
+        match inequality.coefficients[var].signum() {
+            1 => lower.push(inequality),
+            -1 => upper.push(inequality),
+            _ => kept.push(inequality.clone()),
+        }
+    }
+    for low in &lower {
+        for up in &upper {
+            let (a, b) = (low.coefficients[var], -up.coefficients[var]);
+            let combined = Inequality {
+                coefficients: (low.coefficients.iter().zip(&up.coefficients))
+                    .map(|(&l, &u)| b * l + a * u)
+                    .collect(),
+                constant: b * low.constant + a * up.constant,
+            };
+            kept.push(combined.normalised());
+        }
+    }
+    simplify(kept)
+}
+
+/// Keeps the tightest of inequalities sharing their coefficients and drops trivial ones,
+/// or returns `None` if one of them can never hold.
+fn simplify(system: Vec<Inequality>) -> Option<Vec<Inequality>> {
+    let mut tightest: BTreeMap<Vec<i128>, i128> = BTreeMap::new();
+    for inequality in system {
+        if inequality.coefficients.iter().all(|&a| a == 0) {
+            if inequality.constant > 0 {
+                return None;
+            }
+            continue;
+        }
+        let constant = tightest
+            .entry(inequality.coefficients)
+            .or_insert(inequality.constant);
+        *constant = (*constant).max(inequality.constant);
+    }
+    Some(
+        tightest
+            .into_iter()
+            .map(|(coefficients, constant)| Inequality {
+                coefficients,
+                constant,
+            })
+            .collect(),
+    )
+}
+
+/// Integer bounds on variable `var` from a system where it is the only one left. A missing
+/// end means the variable is unbounded that way; the ends cross if no integer fits between.
+fn bounds(system: &[Inequality], var: usize) -> (Option<i128>, Option<i128>) {
+    let (mut low, mut high) = (None::<i128>, None::<i128>);
+    for inequality in system {
+        let (a, c) = (inequality.coefficients[var], inequality.constant);
+        if a > 0 {
+            let bound = -(-c).div_euclid(a);
+            low = Some(low.map_or(bound, |low| low.max(bound)));
+        } else if a < 0 {
+            let bound = (-c).div_euclid(-a);
+            high = Some(high.map_or(bound, |high| high.min(bound)));
+        }
+    }
+    (low, high)
+}
+
+/// Like `bounds`, but `None` when no integer value fits.
+fn range(system: &[Inequality], var: usize) -> Option<(Option<i128>, Option<i128>)> {
+    match bounds(system, var) {
+        (Some(low), Some(high)) if low > high => None,
+        ends => Some(ends),
+    }
+}
+
+/// Whether some non-zero combination `K d` of the kernel columns is non-negative and costs
+/// nothing, meaning the buttons can be pressed more and more forever without moving the
+/// claw or making the presses dearer, so no cost bound ever ends the search. Since `K` has
+/// independent columns, `K d` is non-zero exactly when `d` is, so it is enough to ask for
+/// `K d >= 0` with its entries adding up to at least 1.
+fn has_free_ray(kernel: &[Vec<i128>], costs: &[u64]) -> bool {
+    let dimension = kernel.first().map_or(0, Vec::len);
+    let column = |j: usize, weight: &dyn Fn(usize) -> i128| -> i128 {
+        kernel
+            .iter()
+            .enumerate()
+            .map(|(i, row)| row[j] * weight(i))
+            .sum()
+    };
+    let slope: Vec<i128> = (0..dimension)
+        .map(|j| column(j, &|i| costs[i] as i128))
+        .collect();
+
+    let mut system: Vec<Inequality> = kernel
+        .iter()
+        .map(|row| Inequality {
+            coefficients: row.clone(),
+            constant: 0,
+        })
+        .collect();
+    system.push(Inequality {
+        coefficients: (0..dimension).map(|j| column(j, &|_| 1)).collect(),
+        constant: 1,
+    });
+    for sign in [1, -1] {
+        system.push(Inequality {
+            coefficients: slope.iter().map(|&g| sign * g).collect(),
+            constant: 0,
+        });
+    }
+
+    let mut system = simplify(system);
+    for var in 0..dimension {
+        system = system.and_then(|system| eliminate(&system, var));
+    }
+    system.is_some()
+}
+
+/// How much more the cheapest whole presses can cost than the cheapest fractional ones.
+/// By the proximity theorem of Cook, Gerards, Schrijver and Tardos, some cheapest whole
+/// solution is within `n Δ` presses of a cheapest fractional one on every one of the `n`
+/// buttons, where `Δ` bounds the subdeterminants of the button matrix. By Hadamard's
+/// inequality the product of the rows' lengths bounds `Δ`, and the sums of their absolute
+/// values are no shorter. Clamped so the eliminations it takes part in cannot overflow.
+fn proximity(matrix: &[Vec<i128>], costs: &[u64]) -> i128 {
+    let subdeterminant = matrix.iter().fold(1i128, |product, row| {
+        product.saturating_mul(row.iter().map(|m| m.abs()).sum::<i128>().max(1))
+    });
+    let total: i128 = costs.iter().map(|&c| c as i128).sum();
+    (costs.len() as i128)
+        .saturating_mul(subdeterminant)
+        .saturating_mul(total)
+        .min(i64::MAX as i128)
+}
+
+/// Branch and bound over the kernel coordinates `z` of an extended machine, whose presses
+/// are `x0 + K z`. The variables of every system are `z_0, ..., z_{k-1}` and the extra
+/// cost `t = costs . K z` on top of the cost of `x0`. `systems[j]` is the projection of
+/// all the constraints onto `z_0, ..., z_{j-1}` and `t`, found by eliminating the other
+/// coordinates, so once the first `j` coordinates are fixed it bounds the cheapest cost
+/// any choice of the rest can reach. `None` marks a projection with no solution. Either
+/// the press limit or a ceiling on `t` keeps every coordinate within a finite range.
+struct Search<'a> {
+    x0: &'a [i128],
+    kernel: &'a [Vec<i128>],
+    costs: &'a [u64],
+    limit: Option<i128>,
+    systems: Vec<Option<Vec<Inequality>>>,
+}
+
+impl<'a> Search<'a> {
+    fn new(
+        x0: &'a [i128],
+        kernel: &'a [Vec<i128>],
+        costs: &'a [u64],
+        limit: Option<i128>,
+        ceiling: Option<i128>,
+    ) -> Self {
+        let dimension = kernel.first().map_or(0, Vec::len);
+        let row = |i: usize, sign: i128| -> Vec<i128> {
+            kernel[i].iter().map(|&k| sign * k).chain([0]).collect()
+        };
+
+        // 0 <= x0 + K z <= limit
+        let mut system = Vec::new();
+        for (i, &x) in x0.iter().enumerate() {
+            system.push(Inequality {
+                coefficients: row(i, 1),
+                constant: -x,
+            });
+            if let Some(limit) = limit {
+                system.push(Inequality {
+                    coefficients: row(i, -1),
+                    constant: x - limit,
+                });
+            }
+        }
+
+        // t = costs . K z, as two inequalities
+        let slope: Vec<i128> = (0..dimension)
+            .map(|j| (0..x0.len()).map(|i| kernel[i][j] * costs[i] as i128).sum())
+            .collect();
+        for sign in [1, -1] {
+            system.push(Inequality {
+                coefficients: slope.iter().map(|&g| sign * g).chain([-sign]).collect(),
+                constant: 0,
+            });
+        }
+        if let Some(ceiling) = ceiling {
+            system.push(Inequality {
+                coefficients: vec![0; dimension].into_iter().chain([-1]).collect(),
+                constant: -ceiling,
+            });
+        }
+
+        let mut systems = vec![simplify(system)];
+        for var in (0..dimension).rev() {
+            let projected = systems[0]
+                .as_ref()
+                .and_then(|system| eliminate(system, var));
+            systems.insert(0, projected);
+        }
+        Search {
+            x0,
+            kernel,
+            costs,
+            limit,
+            systems,
+        }
+    }
+
+    fn within_bounds(&self, x: i128) -> bool {
+        x >= 0 && self.limit.is_none_or(|limit| x <= limit)
+    }
+
+    fn presses(&self, z: &[i128]) -> Vec<i128> {
+        self.x0
+            .iter()
+            .zip(self.kernel)
+            .map(|(&x, row)| x + row.iter().zip(z).map(|(&k, &z)| k * z).sum::<i128>())
+            .collect()
+    }
+
+    fn cost(&self, x: &[i128]) -> i128 {
+        x.iter().zip(self.costs).map(|(&n, &c)| n * c as i128).sum()
+    }
+
+    /// `systems[j]` with the coordinates in `z` fixed.
+    fn fixed(&self, j: usize, z: &[i128]) -> Option<Vec<Inequality>> {
+        let system = self.systems[j].as_ref()?;
+        let fixed = system
+            .iter()
+            .map(|inequality| {
+                z.iter()
+                    .enumerate()
+                    .fold(inequality.clone(), |fixed, (var, &value)| {
+                        fixed.substitute(var, value)
+                    })
+            })
+            .collect();
+        simplify(fixed)
+    }
+
+    /// Lowest extra cost `t` reachable once the coordinates in `z` are fixed, if any.
+    fn lowest_cost(&self, z: &[i128]) -> Option<i128> {
+        let dimension = self.systems.len() - 1;
+        let system = self.fixed(z.len(), z)?;
+        let (low, _) = range(&system, dimension)?;
+        // The cost never drops below zero, so a bounded system always has a lowest one
+        low
+    }
+
+    /// Picks each coordinate in turn, starting from the values that allow the lowest cost
+    /// and moving outwards both ways. The lowest cost is a convex function of the
+    /// coordinate, so each way stops for good once it cannot beat the best found so far.
+    fn run(&self, z: &mut Vec<i128>, best: &mut Option<Presses>) {
+        let (j, dimension) = (z.len(), self.systems.len() - 1);
+        let base = self.cost(self.x0);
+        let beaten = |t: i128, best: &Option<Presses>| {
+            best.as_ref().is_some_and(|best| base + t >= best.cost)
+        };
+
+        if j == dimension {
+            let x = self.presses(z);
+            if x.iter().all(|&n| self.within_bounds(n)) {
+                let cost = self.cost(&x);
+                if best.as_ref().is_none_or(|best| cost < best.cost) {
+                    *best = Some(Presses { presses: x, cost });
+                }
+            }
+            return;
+        }
+
+        let Some(lowest) = self.lowest_cost(z) else {
+            return;
+        };
+        if beaten(lowest, best) {
+            return;
+        }
+
+        // Range of z_j, and where in it the lowest cost is reached
+        let Some(next) = self.fixed(j + 1, z) else {
+            return;
+        };
+        let Some((Some(low), Some(high))) =
+            eliminate(&next, dimension).and_then(|system| range(&system, j))
+        else {
+            return;
+        };
+        let Some((Some(floor), _)) =
+            eliminate(&next, j).and_then(|system| range(&system, dimension))
+        else {
+            return;
+        };
+        let at_lowest: Vec<Inequality> = next
+            .iter()
+            .map(|inequality| inequality.substitute(dimension, floor))
+            .collect();
+        let Some(at_lowest) = simplify(at_lowest) else {
+            return;
+        };
+        let start = match bounds(&at_lowest, j) {
+            (Some(from), _) => from,
+            (None, Some(to)) => to,
+            (None, None) => 0,
+        };
+        let start = start.clamp(low, high);
+
+        for direction in [1, -1] {
+            let mut value = if direction == 1 { start } else { start - 1 };
+            while (low..=high).contains(&value) {
+                z.push(value);
+                match self.lowest_cost(z) {
+                    Some(t) if beaten(t, best) => {
+                        z.pop();
+                        break;
+                    }
+                    Some(_) => self.run(z, best),
+                    None => {}
+                }
+                z.pop();
+                value += direction;
+            }
+        }
+    }
+}
+
+/// Why the extended input could not be read, with the 1-based line it happened on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    InvalidLine(usize),
+    UnknownAxis(usize, char),
+    NoButtons(usize),
+    MissingPrize(usize),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::InvalidLine(line) => write!(f, "line {}: not a button or prize", line),
+            ParseError::UnknownAxis(line, axis) => {
+                write!(f, "line {}: the prize has no {} axis", line, axis)
+            }
+            ParseError::NoButtons(line) => write!(f, "line {}: prize without buttons", line),
+            ParseError::MissingPrize(line) => {
+                write!(f, "line {}: buttons without a prize", line)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// A button read before its prize line names the axes: its line, name, moves and cost.
+type PendingButton = (usize, String, Vec<(char, i128)>, Option<u64>);
+
+/// Parse machines with any number of buttons and axes, such as
+///
+/// ```text
+/// Button A: X+94, Y+34, Z+2
+/// Button B: X+22, Y+67, cost 2
+/// Button C: Z+1
+/// Prize: X=8400, Y=5400, Z=170
+/// ```
+///
+/// The prize line names the axes; buttons leave out axes they do not move along, and
+/// may give their own cost. The puzzle's own input is a special case of this format.
+pub fn parse_machines(input: &str) -> Result<Vec<Machine>, ParseError> {
+    let button_rx = Regex::new(r"^Button (\w+):(.*)$").unwrap();
+    let prize_rx = Regex::new(r"^Prize:(.*)$").unwrap();
+    let move_rx = Regex::new(r"^([A-Z])\s*([+-]\d+)$").unwrap();
+    let target_rx = Regex::new(r"^([A-Z])\s*=\s*(-?\d+)$").unwrap();
+    let cost_rx = Regex::new(r"^cost\s*=?\s*(\d+)$").unwrap();
+
+    let mut machines = Vec::new();
+    let mut buttons: Vec<PendingButton> = Vec::new();
+    for (index, line) in input.lines().enumerate() {
+        let (number, line) = (index + 1, line.trim());
+        if line.is_empty() {
+            continue;
+        }
+        // A number too large to hold makes the line as invalid as a malformed one
+        let invalid = || ParseError::InvalidLine(number);
+        let items = |rest: &str| -> Vec<String> {
+            rest.split(',')
+                .map(|item| item.trim().to_string())
+                .filter(|item| !item.is_empty())
+                .collect()
+        };
+
+        if let Some(c) = button_rx.captures(line) {
+            let (mut moves, mut cost) = (Vec::new(), None);
+            for item in items(&c[2]) {
+                if let Some(m) = move_rx.captures(&item) {
+                    let axis = m[1].chars().next().unwrap();
+                    let delta = m[2].parse().map_err(|_| invalid())?;
+                    moves.push((axis, delta));
+                } else if let Some(m) = cost_rx.captures(&item) {
+                    cost = Some(m[1].parse().map_err(|_| invalid())?);
+                } else {
+                    return Err(ParseError::InvalidLine(number));
+                }
+            }
+            buttons.push((number, c[1].to_string(), moves, cost));
+        } else if let Some(c) = prize_rx.captures(line) {
+            let mut axes = Vec::new();
+            let mut prize = Vec::new();
+            for item in items(&c[1]) {
+                let m = target_rx
+                    .captures(&item)
+                    .ok_or(ParseError::InvalidLine(number))?;
+                axes.push(m[1].chars().next().unwrap());
+                prize.push(m[2].parse().map_err(|_| invalid())?);
+            }
+            if buttons.is_empty() {
+                return Err(ParseError::NoButtons(number));
+            }
+
+            let mut machine_buttons = Vec::new();
+            for (line, name, moves, cost) in buttons.drain(..) {
+                let mut vector = vec![0; axes.len()];
+                for (axis, delta) in moves {
+                    let i = axes
+                        .iter()
+                        .position(|&a| a == axis)
+                        .ok_or(ParseError::UnknownAxis(line, axis))?;
+                    vector[i] += delta;
+                }
+                machine_buttons.push(Button {
+                    name,
+                    moves: vector,
+                    cost,
+                });
+            }
+            machines.push(Machine {
+                axes,
+                buttons: machine_buttons,
+                prize,
+            });
+        } else {
+            return Err(ParseError::InvalidLine(number));
+        }
+    }
+
+    match buttons.first() {
+        Some(&(line, ..)) => Err(ParseError::MissingPrize(line)),
+        None => Ok(machines),
+    }
+}
+
+/// Total tokens to win every winnable prize.
+pub fn total_cost(machines: &[Machine], options: &Options) -> i128 {
+    machines
+        .iter()
+        .filter_map(|m| options.solve(m).ok())
+        .map(|presses| presses.cost)
+        .sum()
+}
+
+/// One line per machine with its cheapest presses, or why it cannot be won.
+pub fn explain(machines: &[Machine], options: &Options) -> String {
+    machines
+        .iter()
+        .enumerate()
+        .map(|(i, machine)| match options.solve(machine) {
+            Ok(p) => {
+                let presses: Vec<String> = p
+                    .presses
+                    .iter()
+                    .zip(&machine.buttons)
+                    .map(|(n, button)| format!("{} x {}", n, button.name))
+                    .collect();
+                format!(
+                    "machine {}: {} = {} tokens\n",
+                    i + 1,
+                    presses.join(" + "),
+                    p.cost
+                )
+            }
+            Err(reason) => format!("machine {}: unwinnable, {}\n", i + 1, reason),
+        })
+        .collect()
+}
+
+/// Command-line options shared by both parts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Options {
+    pub costs: Costs,
+    pub offset: Vec<i128>,
+    pub limit: Option<i128>,
+    pub explain: bool,
+}
+
+impl Options {
+    /// The puzzle's costs with every prize coordinate moved by `offset`.
+    pub fn new(offset: i128) -> Self {
+        Options {
+            costs: Costs::default(),
+            offset: vec![offset],
+            limit: None,
+            explain: false,
+        }
+    }
+
+    /// Solve one machine with these options.
+    pub fn solve(&self, machine: &Machine) -> Result<Presses, Unwinnable> {
+        machine
+            .with_offset(&self.offset)
+            .solve(self.costs, self.limit)
+    }
+
+    /// Parse `[--costs A,B] [--offset N|X,Y,...] [--limit N] [--explain]`, starting from
+    /// `Options::new(offset)`.
+    pub fn parse(args: &[String], offset: i128) -> Result<Options, String> {
+        let mut options = Options::new(offset);
+        let list = |value: Option<&String>| -> Option<Vec<i128>> {
+            value?
+                .split(',')
+                .map(|item| item.trim().parse().ok())
+                .collect()
+        };
+
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--costs" => {
+                    let costs = match list(args.next()).as_deref() {
+                        Some(&[a, b]) if a >= 0 && b >= 0 => Costs {
+                            a: a as u64,
+                            b: b as u64,
+                        },
+                        _ => return Err("--costs expects A,B token counts".to_string()),
+                    };
+                    options.costs = costs;
+                }
+                "--offset" => {
+                    options.offset = list(args.next())
+                        .filter(|offset| !offset.is_empty())
+                        .ok_or("--offset expects N or X,Y,...")?;
+                }
+                "--limit" => {
+                    options.limit = Some(
+                        args.next()
+                            .and_then(|n| n.parse().ok())
+                            .filter(|&n: &i128| n >= 0)
+                            .ok_or("--limit expects a number of presses")?,
+                    );
+                }
+                "--explain" => options.explain = true,
+                _ => return Err(format!("Unknown option: {}", arg)),
+            }
+        }
+        Ok(options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::panic;
+    use std::sync::mpsc::{self, RecvTimeoutError};
+    use std::thread;
+    use std::time::Duration;
+
+    fn machine(a: (i128, i128), b: (i128, i128), prize: (i128, i128)) -> ClawMachine {
+        ClawMachine { a, b, prize }
+    }
+
+    #[test]
+    fn test_independent_buttons() {
+        let costs = Costs::default();
+        let cm = machine((94, 34), (22, 67), (8400, 5400));
+        assert_eq!(
+            cm.solve(costs),
+            Ok(Solution {
+                a: 80,
+                b: 40,
+                cost: 280
+            })
+        );
+
+        let cm = machine((26, 66), (67, 21), (12748, 12176));
+        let Err(Unwinnable::FractionalPresses { a, b }) = cm.solve(costs) else {
+            panic!("expected fractional presses");
+        };
+        assert!(a.denominator > 1 && b.denominator > 1);
+
+        let cm = machine((1, 0), (0, 1), (-2, 3));
+        assert_eq!(
+            cm.solve(costs),
+            Err(Unwinnable::NegativePresses { a: -2, b: 3 })
+        );
+    }
+
+    #[test]
+    fn test_collinear_buttons() {
+        let costs = Costs::default();
+
+        // B is cheaper per step, so use as many B presses as possible
+        let cm = machine((2, 4), (3, 6), (13, 26));
+        assert_eq!(
+            cm.solve(costs),
+            Ok(Solution {
+                a: 2,
+                b: 3,
+                cost: 9
+            })
+        );
+
+        // With A much cheaper per step, use as many A presses as possible instead
+        let costs_a = Costs { a: 1, b: 10 };
+        assert_eq!(
+            cm.solve(costs_a),
+            Ok(Solution {
+                a: 5,
+                b: 1,
+                cost: 15
+            })
+        );
+
+        assert_eq!(
+            machine((2, 4), (3, 6), (13, 27)).solve(costs),
+            Err(Unwinnable::OffLine)
+        );
+        assert_eq!(
+            machine((2, 4), (4, 8), (3, 6)).solve(costs),
+            Err(Unwinnable::Indivisible {
+                gcd: 2,
+                distance: 3
+            })
+        );
+        assert_eq!(
+            machine((2, 4), (3, 6), (1, 2)).solve(costs),
+            Err(Unwinnable::NoNonNegative)
+        );
+
+        // Buttons pointing opposite ways along the line still combine
+        assert_eq!(
+            machine((0, 5), (0, -3), (0, 1)).solve(costs),
+            Ok(Solution {
+                a: 2,
+                b: 3,
+                cost: 9
+            })
+        );
+        assert_eq!(
+            machine((0, 0), (0, 0), (0, 0)).solve(costs),
+            Ok(Solution {
+                a: 0,
+                b: 0,
+                cost: 0
+            })
+        );
+        assert_eq!(
+            machine((0, 0), (0, 0), (1, 0)).solve(costs),
+            Err(Unwinnable::Stuck)
+        );
+        assert_eq!(
+            machine((0, 0), (7, 0), (21, 0)).solve(costs),
+            Ok(Solution {
+                a: 0,
+                b: 3,
+                cost: 3
+            })
+        );
+    }
+
+    #[test]
+    fn test_collinear_matches_brute_force() {
+        for costs in [Costs::default(), Costs { a: 1, b: 5 }, Costs { a: 2, b: 2 }] {
+            for (alpha, beta) in [(2, 3), (4, 6), (3, -2), (5, 5), (0, 4), (-3, 7)] {
+                for pi in -5..40 {
+                    let cm = machine((alpha, 2 * alpha), (beta, 2 * beta), (pi, 2 * pi));
+                    let best = (0..60)
+                        .flat_map(|n| (0..60).map(move |m| (n, m)))
+                        .filter(|&(n, m)| n * alpha + m * beta == pi)
+                        .map(|(n, m)| n * costs.a as i128 + m * costs.b as i128)
+                        .min();
+                    assert_eq!(
+                        cm.solve(costs).ok().map(|s| s.cost),
+                        best,
+                        "{:?} {:?}",
+                        cm,
+                        costs
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_explain() {
+        let machines = parse_machines(
+            "Button A: X+94, Y+34\nButton B: X+22, Y+67\nPrize: X=8400, Y=5400\n\n\
+             Button A: X+2, Y+4\nButton B: X+3, Y+6\nPrize: X=13, Y=27\n\n\
+             Button A: X+2, Y+1, Z+1\nButton B: X+1, Y+2, Z+1\nButton C: X+1, Y+1, Z+1, cost 2\nPrize: X=4, Y=4, Z=3",
+        )
+        .unwrap();
+        assert_eq!(
+            explain(&machines, &Options::new(0)),
+            "machine 1: 80 x A + 40 x B = 280 tokens\n\
+             machine 2: unwinnable, both buttons move along the same line and the prize is not on it\n\
+             machine 3: 1 x A + 1 x B + 1 x C = 6 tokens\n"
+        );
+    }
+
+    #[test]
+    fn test_options() {
+        let args: Vec<String> = [
+            "--costs",
+            "1,3",
+            "--offset",
+            "5,7",
+            "--limit",
+            "100",
+            "--explain",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+        assert_eq!(
+            Options::parse(&args, 0),
+            Ok(Options {
+                costs: Costs { a: 1, b: 3 },
+                offset: vec![5, 7],
+                limit: Some(100),
+                explain: true
+            })
+        );
+        assert_eq!(
+            Options::parse(&[], PART_2_OFFSET).unwrap().offset,
+            vec![PART_2_OFFSET]
+        );
+        assert!(Options::parse(&["--costs".to_string(), "x".to_string()], 0).is_err());
+    }
+
+    #[test]
+    fn test_parse_machines() {
+        let machines = parse_machines(
+            "Button A: X+94, Y+34, Z+2\n\
+             Button B: X+22, Y+67, cost 2\n\
+             Button C: Z+1\n\
+             Prize: X=8400, Y=5400, Z=170",
+        )
+        .unwrap();
+        assert_eq!(machines.len(), 1);
+        assert_eq!(machines[0].axes, vec!['X', 'Y', 'Z']);
+        assert_eq!(machines[0].prize, vec![8400, 5400, 170]);
+        assert_eq!(machines[0].buttons[1].moves, vec![22, 67, 0]);
+        assert_eq!(machines[0].buttons[2].moves, vec![0, 0, 1]);
+        assert_eq!(machines[0].button_costs(Costs::default()), vec![3, 2, 1]);
+        assert_eq!(
+            machines[0].solve(Costs::default(), None),
+            Ok(Presses {
+                presses: vec![80, 40, 10],
+                cost: 330
+            })
+        );
+
+        // The puzzle's format reads the same as with the two-button parser
+        let input = include_str!("../docs/challenge_1.txt");
+        let classic: Vec<Machine> = parse(input).iter().map(Machine::from).collect();
+        assert_eq!(parse_machines(input).unwrap(), classic);
+
+        assert_eq!(
+            parse_machines("Button A: X+1\nButton B: Y+1"),
+            Err(ParseError::MissingPrize(1))
+        );
+        assert_eq!(
+            parse_machines("Button A: X+1, W+2\nPrize: X=1"),
+            Err(ParseError::UnknownAxis(1, 'W'))
+        );
+        assert_eq!(parse_machines("Prize: X=1"), Err(ParseError::NoButtons(1)));
+        assert_eq!(
+            parse_machines("Button A: X*1\nPrize: X=1"),
+            Err(ParseError::InvalidLine(1))
+        );
+        let huge = "99999999999999999999999999999999999999999";
+        assert_eq!(
+            parse_machines(&format!("Button A: X+{huge}\nPrize: X=1")),
+            Err(ParseError::InvalidLine(1))
+        );
+        assert_eq!(
+            parse_machines(&format!("Button A: X+1, cost {huge}\nPrize: X=1")),
+            Err(ParseError::InvalidLine(1))
+        );
+        assert_eq!(
+            parse_machines(&format!("Button A: X+1\nPrize: X={huge}")),
+            Err(ParseError::InvalidLine(2))
+        );
+    }
+
+    #[test]
+    fn test_hermite() {
+        let matrix = vec![vec![4, 6, 2], vec![1, 2, 3]];
+        let (h, u, pivots) = hermite(&matrix, 3);
+        assert_eq!(pivots, vec![0, 1]);
+        assert_eq!(h[0][0], 2);
+        assert_eq!((h[0][1], h[0][2], h[1][2]), (0, 0, 0));
+        // h = matrix * u
+        for (r, row) in matrix.iter().enumerate() {
+            for c in 0..3 {
+                let product: i128 = (0..3).map(|k| row[k] * u[k][c]).sum();
+                assert_eq!(product, h[r][c]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_lattice_matches_two_buttons() {
+        let costs = Costs::default();
+        let input = include_str!("../docs/challenge_1.txt");
+        for offset in [0, PART_2_OFFSET] {
+            for cm in parse(input) {
+                let cm = cm.with_offset((offset, offset));
+                assert_eq!(
+                    Machine::from(&cm)
+                        .solve_lattice(&[3, 1], None)
+                        .ok()
+                        .map(|p| p.cost),
+                    cm.solve(costs).ok().map(|s| s.cost)
+                );
+            }
+        }
+
+        for (alpha, beta) in [(2, 3), (4, 6), (3, -2), (5, 5), (0, 4), (-3, 7)] {
+            for pi in -5..40 {
+                let cm = machine((alpha, 2 * alpha), (beta, 2 * beta), (pi, 2 * pi));
+                assert_eq!(
+                    Machine::from(&cm)
+                        .solve_lattice(&[3, 1], None)
+                        .ok()
+                        .map(|p| p.cost),
+                    cm.solve(costs).ok().map(|s| s.cost),
+                    "{:?}",
+                    cm
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_lattice_matches_brute_force() {
+        let limit = 12;
+        let buttons = [
+            vec![vec![3, 1], vec![1, 2], vec![2, 2]],
+            vec![vec![1, 0, 2], vec![0, 1, 1], vec![1, 1, 3], vec![2, 1, 0]],
+            vec![vec![5], vec![3], vec![-2]],
+            vec![vec![2, -1], vec![-1, 2], vec![1, 1]],
+        ];
+        for moves in buttons {
+            let axes = moves[0].len();
+            let costs: Vec<u64> = (0..moves.len() as u64).map(|i| 1 + i * 2 % 5).collect();
+            let machine = |prize: Vec<i128>| Machine {
+                axes: "XYZ".chars().take(axes).collect(),
+                buttons: moves
+                    .iter()
+                    .zip(&costs)
+                    .map(|(m, &c)| Button {
+                        name: "N".to_string(),
+                        moves: m.clone(),
+                        cost: Some(c),
+                    })
+                    .collect(),
+                prize,
+            };
+
+            // Every combination of presses within the limit, and the cheapest per prize
+            let mut cheapest = std::collections::HashMap::new();
+            let mut presses = vec![0i128; moves.len()];
+            loop {
+                let prize: Vec<i128> = (0..axes)
+                    .map(|a| (0..moves.len()).map(|b| moves[b][a] * presses[b]).sum())
+                    .collect();
+                let cost: i128 = presses
+                    .iter()
+                    .zip(&costs)
+                    .map(|(&n, &c)| n * c as i128)
+                    .sum();
+                let entry = cheapest.entry(prize).or_insert(cost);
+                *entry = (*entry).min(cost);
+
+                let Some(i) = presses.iter().position(|&n| n < limit) else {
+                    break;
+                };
+                presses[..i].iter_mut().for_each(|n| *n = 0);
+                presses[i] += 1;
+            }
+
+            for (prize, cost) in cheapest {
+                let solved = machine(prize.clone()).solve_lattice(&costs, Some(limit));
+                assert_eq!(solved.map(|p| p.cost), Ok(cost), "{:?} {:?}", moves, prize);
+            }
+            let unreachable = machine(vec![1000; axes]).solve_lattice(&costs, Some(limit));
+            assert!(unreachable.is_err());
+        }
+    }
+
+    // Machine with one button per list of moves, each costing 1 unless `costs` says otherwise
+    fn lattice_machine(moves: &[Vec<i128>], prize: Vec<i128>) -> Machine {
+        Machine {
+            axes: "XYZ".chars().take(prize.len()).collect(),
+            buttons: moves
+                .iter()
+                .map(|moves| Button {
+                    name: "N".to_string(),
+                    moves: moves.clone(),
+                    cost: Some(1),
+                })
+                .collect(),
+            prize,
+        }
+    }
+
+    #[test]
+    fn test_lattice_bounded_by_all_rows() {
+        // No single axis bounds any button, yet no mix of presses cancels out
+        let moves = [vec![-1, 2], vec![2, -1], vec![2, 2], vec![-2, 2]];
+        let machine = lattice_machine(&moves, vec![3, 6]);
+        assert_eq!(
+            machine.solve_lattice(&[1, 1, 1, 1], None),
+            Ok(Presses {
+                presses: vec![1, 0, 2, 0],
+                cost: 3
+            })
+        );
+        assert_eq!(
+            machine
+                .solve_lattice(&[1, 1, 1, 1], Some(40))
+                .map(|p| p.cost),
+            Ok(3)
+        );
+
+        // Far away prizes are found without walking every press count in between
+        let far = lattice_machine(&moves, vec![3_000_000_000_003, 6_000_000_000_006]);
+        let presses = far.solve_lattice(&[1, 1, 1, 1], None).unwrap();
+        let reached: Vec<i128> = (0..2)
+            .map(|a| (0..4).map(|b| moves[b][a] * presses.presses[b]).sum())
+            .collect();
+        assert_eq!(reached, far.prize);
+        assert_eq!(presses.cost, 3_000_000_000_003);
+    }
+
+    // Runs `check` on a thread of its own, failing if it is not done within `seconds`
+    fn with_deadline(seconds: u64, check: impl FnOnce() + Send + 'static) {
+        let (done, finished) = mpsc::channel();
+        let worker = thread::spawn(move || {
+            check();
+            done.send(()).unwrap();
+        });
+        match finished.recv_timeout(Duration::from_secs(seconds)) {
+            Ok(()) => {}
+            Err(RecvTimeoutError::Timeout) => panic!("not done within {seconds} s"),
+            Err(RecvTimeoutError::Disconnected) => panic::resume_unwind(worker.join().unwrap_err()),
+        }
+    }
+
+    #[test]
+    fn test_lattice_random_machines() {
+        with_deadline(120, random_machines);
+    }
+
+    fn random_machines() {
+        let mut state = 0x9e37_79b9_7f4a_7c15u64;
+        let mut random = |n: u64| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state % n
+        };
+
+        let box_limit = 8;
+        for _ in 0..300 {
+            let (axes, buttons) = (1 + random(2) as usize, 2 + random(3) as usize);
+            let moves: Vec<Vec<i128>> = (0..buttons)
+                .map(|_| (0..axes).map(|_| random(7) as i128 - 3).collect())
+                .collect();
+            let costs: Vec<u64> = (0..buttons).map(|_| random(5)).collect();
+
+            // Cheapest presses within the box for every prize they reach
+            let mut cheapest = HashMap::new();
+            let mut presses = vec![0i128; buttons];
+            loop {
+                let prize: Vec<i128> = (0..axes)
+                    .map(|a| (0..buttons).map(|b| moves[b][a] * presses[b]).sum())
+                    .collect();
+                let cost: i128 = presses
+                    .iter()
+                    .zip(&costs)
+                    .map(|(&n, &c)| n * c as i128)
+                    .sum();
+                let entry = cheapest.entry(prize).or_insert(cost);
+                *entry = (*entry).min(cost);
+
+                let Some(i) = presses.iter().position(|&n| n < box_limit) else {
+                    break;
+                };
+                presses[..i].iter_mut().for_each(|n| *n = 0);
+                presses[i] += 1;
+            }
+
+            // Prizes the box reaches, and small ones it may well not
+            let mut prizes: Vec<Vec<i128>> = cheapest.keys().take(40).cloned().collect();
+            prizes.extend((0..20).map(|_| (0..axes).map(|_| random(25) as i128 - 12).collect()));
+
+            // Only buttons that cancel out for free leave the search without an end
+            let free_ray = {
+                let machine = lattice_machine(&moves, vec![0; axes]);
+                machine.solve_lattice(&costs, None) == Err(Unwinnable::Unbounded)
+            };
+            for prize in prizes {
+                let machine = lattice_machine(&moves, prize.clone());
+                let cost = cheapest.get(&prize).copied();
+
+                // With the limit the box is the whole search space
+                let limited = machine.solve_lattice(&costs, Some(box_limit));
+                assert_eq!(limited.ok().map(|p| p.cost), cost, "{moves:?} {prize:?}");
+
+                // Without it, a cheaper answer may lie outside the box but never a dearer one
+                match machine.solve_lattice(&costs, None) {
+                    Err(Unwinnable::Unbounded) => assert!(free_ray, "{moves:?}"),
+                    Err(_) => assert!(cost.is_none(), "{moves:?} {prize:?}"),
+                    Ok(solved) => {
+                        assert!(!free_ray, "{moves:?}");
+                        let reached: Vec<i128> = (0..axes)
+                            .map(|a| (0..buttons).map(|b| moves[b][a] * solved.presses[b]).sum())
+                            .collect();
+                        assert_eq!(reached, prize, "{moves:?}");
+                        assert!(
+                            solved.presses.iter().all(|&n| n >= 0),
+                            "{moves:?} {prize:?}"
+                        );
+                        assert!(
+                            cost.is_none_or(|cost| solved.cost <= cost),
+                            "{moves:?} {prize:?}"
+                        );
+                        if solved.presses.iter().all(|&n| n <= box_limit) {
+                            assert_eq!(Some(solved.cost), cost, "{moves:?} {prize:?}");
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_lattice_failures() {
+        let machine = |moves: Vec<Vec<i128>>, prize: Vec<i128>| Machine {
+            axes: "XYZ".chars().take(prize.len()).collect(),
+            buttons: moves
+                .into_iter()
+                .map(|moves| Button {
+                    name: "N".to_string(),
+                    moves,
+                    cost: Some(1),
+                })
+                .collect(),
+            prize,
+        };
+
+        let planar = machine(
+            vec![vec![1, 0, 0], vec![0, 1, 0], vec![1, 1, 0]],
+            vec![1, 1, 1],
+        );
+        assert_eq!(
+            planar.solve_lattice(&[1, 1, 1], None),
+            Err(Unwinnable::OutOfSpan)
+        );
+        let even = machine(vec![vec![2, 0], vec![0, 2], vec![2, 2]], vec![3, 2]);
+        assert_eq!(
+            even.solve_lattice(&[1, 1, 1], None),
+            Err(Unwinnable::OffLattice)
+        );
+        let backwards = machine(vec![vec![1, 1], vec![1, 2], vec![2, 3]], vec![-1, -1]);
+        assert_eq!(
+            backwards.solve_lattice(&[1, 1, 1], None),
+            Err(Unwinnable::Infeasible)
+        );
+
+        // Buttons that cancel out for free can be pressed forever unless limited, while
+        // ones that cost something are simply never worth pressing
+        let loops = machine(vec![vec![1], vec![-1], vec![-2]], vec![0]);
+        assert_eq!(
+            loops.solve_lattice(&[0, 0, 1], None),
+            Err(Unwinnable::Unbounded)
+        );
+        assert_eq!(
+            loops.solve_lattice(&[1, 1, 1], None),
+            Ok(Presses {
+                presses: vec![0, 0, 0],
+                cost: 0
+            })
+        );
+        assert_eq!(
+            loops.solve_lattice(&[1, 1, 1], Some(5)),
+            Ok(Presses {
+                presses: vec![0, 0, 0],
+                cost: 0
+            })
+        );
+
+        // Fractional presses reach X=5 but whole ones never do, limit or not
+        with_deadline(10, || {
+            let gaps = "Button A: X+0, cost 1
+Button B: X+3, cost 3
+Button C: X+4, cost 3
+Button D: X+3, cost 1
+Prize: X=5";
+            let gaps = &parse_machines(gaps).unwrap()[0];
+            assert_eq!(
+                gaps.solve(Costs::default(), None),
+                Err(Unwinnable::Infeasible)
+            );
+            assert_eq!(
+                gaps.solve(Costs::default(), Some(6)),
+                Err(Unwinnable::Infeasible)
+            );
+        });
+    }
+}
@@ -1,60 +1,96 @@
-extern crate regex;
+use day_14::{Simulation, render_heatmap};
 
-use regex::Regex;
-
-// Function to calculate the safety factor for the robots' positions after 100 seconds
-fn solve(input: &str, lim_x: i32, lim_y: i32) -> u32 {
-    let rx = Regex::new(r"p=(\d+),(\d+) v=(-?\d+),(-?\d+)").unwrap();
-
-    // Function to parse integer from string
-    let parse_int = |s: &str| -> i32 { s.parse().unwrap() };
-
-    // Iterate through each line, extract robot position and velocity, calculate the robot's quadrant, and count the robots in each quadrant
-    input
-        .lines()
-        .fold([0u32; 4], |mut acc, line| {
-            if let Some(caps) = rx.captures(line) {
-                let x0 = parse_int(&caps[1]);
-                let y0 = parse_int(&caps[2]);
-                let vx = parse_int(&caps[3]);
-                let vy = parse_int(&caps[4]);
-
-                // Calculate the robot's position after 100 seconds with wrapping
-                let x1 = (x0 + 100 * vx).rem_euclid(lim_x);
-                let y1 = (y0 + 100 * vy).rem_euclid(lim_y);
-
-                // Skip robots that end up on the horizontal or vertical center
-                if x1 == lim_x / 2 || y1 == lim_y / 2 {
-                    return acc;
-                }
+// Function to calculate the safety factor for the robots' positions after `tick` seconds
+fn solve(input: &str, lim_x: i64, lim_y: i64, tick: i64) -> u64 {
+    Simulation::parse(input, lim_x, lim_y).safety_factor(tick)
+}
 
-                // Determine the quadrant based on robot's final position
-                let in_upper_half = y1 < lim_y / 2;
-                let in_left_half = x1 < lim_x / 2;
+// Describe the arena at `tick`: the period, the quadrants, the largest cluster and a heatmap
+// with blocks of `block` x `block` tiles
+fn report(simulation: &Simulation, tick: i64, block: i64) -> String {
+    let [top_left, top_right, bottom_left, bottom_right] = simulation.quadrant_counts(tick);
+    let cluster = simulation.largest_cluster(tick);
+    let mut report = format!(
+        "period: {}\nquadrants: {} {} / {} {}\nlargest cluster: {} tiles",
+        simulation.period(),
+        top_left,
+        top_right,
+        bottom_left,
+        bottom_right,
+        cluster.len()
+    );
+    if let Some((x, y)) = cluster.first() {
+        report += &format!(" from {},{}", x, y);
+    }
+    report += "\n";
+    report += &render_heatmap(&simulation.heatmap(tick, block, block));
+    report
+}
 
-                let qua = (in_upper_half as usize) << 1 | in_left_half as usize;
-                acc[qua] += 1;
-            }
-            acc
-        })
-        // Calculate the safety factor by multiplying the number of robots in each quadrant
-        .iter()
-        .product()
+// Parse a `W,H` or `WxH` arena size
+fn parse_size(size: &str) -> Option<(i64, i64)> {
+    let (width, height) = size.split_once(',').or_else(|| size.split_once('x'))?;
+    let (width, height) = (width.trim().parse().ok()?, height.trim().parse().ok()?);
+    (width > 0 && height > 0).then_some((width, height))
 }
 
 fn main() {
     // Collect command-line arguments, expecting the first argument to be the input file name
     let args: Vec<String> = std::env::args().collect();
     if args.len() < 2 {
-        eprintln!("Usage: {} <input_file>", args[0]);
+        eprintln!(
+            "Usage: {} <input_file> [--size W,H] [--tick N] [--report [BLOCK]]",
+            args[0]
+        );
         std::process::exit(1); // Exit if no input file is provided
     }
 
     // Read the content of the input file
     let input = std::fs::read_to_string(&args[1]).expect("Failed to read input file");
 
-    // Print the safety factor after 100 seconds
-    println!("result: {}", solve(&input, 101, 103));
+    let fail = |message: &str| -> ! {
+        eprintln!("{}", message);
+        std::process::exit(1);
+    };
+
+    let (mut width, mut height) = (101, 103);
+    let mut tick = 100;
+    let mut block = None;
+    let mut options = args[2..].iter().peekable();
+    while let Some(option) = options.next() {
+        match option.as_str() {
+            "--size" => {
+                (width, height) = options
+                    .next()
+                    .and_then(|size| parse_size(size))
+                    .unwrap_or_else(|| fail("--size expects W,H"))
+            }
+            "--tick" => {
+                tick = options
+                    .next()
+                    .and_then(|n| n.parse().ok())
+                    .unwrap_or_else(|| fail("--tick expects a number"))
+            }
+            "--report" => {
+                block = Some(match options.next_if(|n| !n.starts_with("--")) {
+                    Some(n) => n
+                        .parse()
+                        .ok()
+                        .filter(|&n| n > 0)
+                        .unwrap_or_else(|| fail("--report expects a positive block size")),
+                    None => 4,
+                })
+            }
+            _ => fail(&format!("Unknown option: {}", option)),
+        }
+    }
+
+    // Print the safety factor at the chosen tick, 100 seconds by default
+    println!("result: {}", solve(&input, width, height, tick));
+    if let Some(block) = block {
+        let simulation = Simulation::parse(&input, width, height);
+        print!("{}", report(&simulation, tick, block));
+    }
 }
 
 #[cfg(test)]
@@ -82,13 +118,30 @@ p=9,5 v=-3,-3";
     #[test]
     fn test_example() {
         // Test safety factor calculation with example input
-        assert_eq!(solve(EXAMPLE, 11, 7), 12);
+        assert_eq!(solve(EXAMPLE, 11, 7, 100), 12);
     }
 
     #[test]
     fn test_with_input() {
         // Test safety factor with input from a file
         let input = &get_input("./docs/challenge_1.txt");
-        assert_eq!(solve(input, 101, 103), 214400550);
+        assert_eq!(solve(input, 101, 103, 100), 214400550);
+    }
+
+    #[test]
+    fn test_report() {
+        let simulation = Simulation::parse(EXAMPLE, 11, 7);
+        assert_eq!(
+            report(&simulation, 100, 4),
+            "period: 77\nquadrants: 1 3 / 4 1\nlargest cluster: 2 tiles from 1,3\n#+-\n+@ \n"
+        );
+    }
+
+    #[test]
+    fn test_parse_size() {
+        assert_eq!(parse_size("11,7"), Some((11, 7)));
+        assert_eq!(parse_size("101x103"), Some((101, 103)));
+        assert_eq!(parse_size("0,7"), None);
+        assert_eq!(parse_size("11"), None);
     }
 }
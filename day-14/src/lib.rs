@@ -0,0 +1,548 @@
+extern crate regex;
+
+use regex::Regex;
+use std::fmt;
+
+// A robot with its starting position and its velocity in tiles per second
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Robot {
+    pub position: (i64, i64),
+    pub velocity: (i64, i64),
+}
+
+impl Robot {
+    // Position of the robot after `tick` seconds in a `width` x `height` arena that wraps around
+    pub fn position_at(&self, tick: i64, width: i64, height: i64) -> (i64, i64) {
+        (
+            (self.position.0 + tick * self.velocity.0).rem_euclid(width),
+            (self.position.1 + tick * self.velocity.1).rem_euclid(height),
+        )
+    }
+}
+
+// Parse every `p=x,y v=dx,dy` line of the input, skipping anything else
+pub fn parse_robots(input: &str) -> Vec<Robot> {
+    let rx = Regex::new(r"p=(-?\d+),(-?\d+) v=(-?\d+),(-?\d+)").unwrap();
+    let parse_int = |s: &str| -> i64 { s.parse().unwrap() };
+
+    input
+        .lines()
+        .filter_map(|line| rx.captures(line))
+        .map(|caps| Robot {
+            position: (parse_int(&caps[1]), parse_int(&caps[2])),
+            velocity: (parse_int(&caps[3]), parse_int(&caps[4])),
+        })
+        .collect()
+}
+
+// Positions of all robots after `tick` seconds
+pub fn positions_at(robots: &[Robot], tick: i64, width: i64, height: i64) -> Vec<(i64, i64)> {
+    robots
+        .iter()
+        .map(|robot| robot.position_at(tick, width, height))
+        .collect()
+}
+
+// Draw the arena the way the puzzle does: the robot count of each tile, or `.` if empty
+pub fn render(positions: &[(i64, i64)], width: i64, height: i64) -> String {
+    let mut counts = vec![0u32; (width * height) as usize];
+    for &(x, y) in positions {
+        counts[(y * width + x) as usize] += 1;
+    }
+
+    let mut frame = String::with_capacity(((width + 1) * height) as usize);
+    for row in counts.chunks(width as usize) {
+        for &count in row {
+            frame.push(match count {
+                0 => '.',
+                1..=9 => char::from_digit(count, 10).unwrap(),
+                _ => '+',
+            });
+        }
+        frame.push('\n');
+    }
+    frame
+}
+
+// The statistic scored on each axis; lower means the robots are more ordered
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Signal {
+    // Variance of the robot coordinates along the axis
+    #[default]
+    Variance,
+    // Shannon entropy of the robot count per row or column, in bits
+    Entropy,
+}
+
+impl Signal {
+    pub fn parse(name: &str) -> Option<Signal> {
+        match name {
+            "variance" => Some(Signal::Variance),
+            "entropy" => Some(Signal::Entropy),
+            _ => None,
+        }
+    }
+
+    // Score one axis of a frame, given the coordinates of every robot along it
+    pub fn score(&self, coordinates: &[i64], size: i64) -> f64 {
+        let n = coordinates.len() as f64;
+        if coordinates.is_empty() {
+            return 0.0;
+        }
+        match self {
+            Signal::Variance => {
+                let mean = coordinates.iter().sum::<i64>() as f64 / n;
+                coordinates
+                    .iter()
+                    .map(|&c| (c as f64 - mean).powi(2))
+                    .sum::<f64>()
+                    / n
+            }
+            Signal::Entropy => {
+                let mut histogram = vec![0usize; size as usize];
+                for &c in coordinates {
+                    histogram[c as usize] += 1;
+                }
+                histogram
+                    .iter()
+                    .filter(|&&count| count > 0)
+                    .map(|&count| {
+                        let p = count as f64 / n;
+                        -p * p.log2()
+                    })
+                    .sum()
+            }
+        }
+    }
+}
+
+impl fmt::Display for Signal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Signal::Variance => write!(f, "variance"),
+            Signal::Entropy => write!(f, "entropy"),
+        }
+    }
+}
+
+// The best tick on one axis, within that axis' period
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AxisMinimum {
+    pub tick: i64,
+    pub score: f64,
+    pub confidence: f64,
+}
+
+// Find the tick in `0..period` with the lowest score on one axis.
+// A robot's coordinate on an axis repeats with the size of that axis, so `period` ticks cover
+// every frame that axis can show. The confidence is how far the minimum sits below the other
+// ticks, as `1 - 1/z^2` for a z-score of `z` (Chebyshev's bound on how often noise gets that low).
+pub fn axis_minimum(
+    signal: Signal,
+    coordinates: impl Fn(i64) -> Vec<i64>,
+    period: i64,
+) -> AxisMinimum {
+    let scores: Vec<f64> = (0..period)
+        .map(|tick| signal.score(&coordinates(tick), period))
+        .collect();
+
+    let (tick, &best) = scores
+        .iter()
+        .enumerate()
+        .min_by(|a, b| a.1.total_cmp(b.1))
+        .unwrap();
+
+    let others = scores.len() as f64 - 1.0;
+    let confidence = if others > 0.0 {
+        let mean = (scores.iter().sum::<f64>() - best) / others;
+        let variance = scores
+            .iter()
+            .enumerate()
+            .filter(|&(t, _)| t != tick)
+            .map(|(_, &score)| (score - mean).powi(2))
+            .sum::<f64>()
+            / others;
+        let z = (mean - best) / variance.sqrt();
+        if z.is_finite() && z > 1.0 {
+            1.0 - 1.0 / (z * z)
+        } else {
+            0.0
+        }
+    } else {
+        0.0
+    };
+
+    AxisMinimum {
+        tick: tick as i64,
+        score: best,
+        confidence,
+    }
+}
+
+// Extended Euclid: returns (g, x, y) with a * x + b * y = g = gcd(a, b)
+fn extended_gcd(a: i64, b: i64) -> (i64, i64, i64) {
+    if b == 0 {
+        (a, 1, 0)
+    } else {
+        let (g, x, y) = extended_gcd(b, a % b);
+        (g, y, x - (a / b) * y)
+    }
+}
+
+// Chinese remainder theorem for two congruences t = a (mod m) and t = b (mod n).
+// Moduli do not need to be coprime; returns the smallest non-negative t and lcm(m, n),
+// or None when the congruences contradict each other.
+pub fn crt(a: i64, m: i64, b: i64, n: i64) -> Option<(i64, i64)> {
+    let (g, p, _) = extended_gcd(m, n);
+    if (b - a) % g != 0 {
+        return None;
+    }
+    let lcm = m / g * n;
+    let k = ((b - a) / g % (n / g) * p).rem_euclid(n / g);
+    Some(((a + m * k).rem_euclid(lcm), lcm))
+}
+
+// The frame the detector picked
+#[derive(Debug, Clone, PartialEq)]
+pub struct Detection {
+    pub tick: i64,
+    pub confidence: f64,
+    pub frame: String,
+}
+
+// Look for the easter egg frame without simulating every tick.
+// The x coordinates repeat every `width` ticks and the y coordinates every `height` ticks, so
+// the most ordered columns and rows are found independently, scanning `width + height` frames
+// in total, and the tick where both happen is recovered with the CRT.
+pub fn find_tree(robots: &[Robot], width: i64, height: i64, signal: Signal) -> Option<Detection> {
+    if robots.is_empty() {
+        return None;
+    }
+
+    let xs = axis_minimum(
+        signal,
+        |tick| {
+            robots
+                .iter()
+                .map(|r| (r.position.0 + tick * r.velocity.0).rem_euclid(width))
+                .collect()
+        },
+        width,
+    );
+    let ys = axis_minimum(
+        signal,
+        |tick| {
+            robots
+                .iter()
+                .map(|r| (r.position.1 + tick * r.velocity.1).rem_euclid(height))
+                .collect()
+        },
+        height,
+    );
+
+    let (tick, _) = crt(xs.tick, width, ys.tick, height)?;
+    Some(Detection {
+        tick,
+        confidence: xs.confidence.min(ys.confidence),
+        frame: render(&positions_at(robots, tick, width, height), width, height),
+    })
+}
+
+// Greatest common divisor and least common multiple of non-negative numbers
+fn gcd(a: i64, b: i64) -> i64 {
+    extended_gcd(a, b).0
+}
+
+fn lcm(a: i64, b: i64) -> i64 {
+    a / gcd(a, b) * b
+}
+
+// Robots in a wrapping arena of a given size, answering questions about any tick
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Simulation {
+    pub robots: Vec<Robot>,
+    pub width: i64,
+    pub height: i64,
+}
+
+impl Simulation {
+    pub fn new(robots: Vec<Robot>, width: i64, height: i64) -> Self {
+        assert!(width > 0 && height > 0, "the arena must not be empty");
+        Simulation {
+            robots,
+            width,
+            height,
+        }
+    }
+
+    pub fn parse(input: &str, width: i64, height: i64) -> Self {
+        Simulation::new(parse_robots(input), width, height)
+    }
+
+    // Positions of all robots after `tick` seconds
+    pub fn positions_at(&self, tick: i64) -> Vec<(i64, i64)> {
+        positions_at(&self.robots, tick, self.width, self.height)
+    }
+
+    // Ticks after which the x coordinates, then the y coordinates, all repeat.
+    // A robot with velocity `v` on an axis of size `n` comes back after `n / gcd(v, n)` ticks.
+    pub fn axis_periods(&self) -> (i64, i64) {
+        let axis = |size: i64, velocity: fn(&Robot) -> i64| {
+            self.robots
+                .iter()
+                .map(|robot| size / gcd(velocity(robot).rem_euclid(size), size))
+                .fold(1, lcm)
+        };
+        (
+            axis(self.width, |robot| robot.velocity.0),
+            axis(self.height, |robot| robot.velocity.1),
+        )
+    }
+
+    // Ticks after which the whole arena looks the same again
+    pub fn period(&self) -> i64 {
+        let (x, y) = self.axis_periods();
+        lcm(x, y)
+    }
+
+    // Robots in each quadrant after `tick` seconds, as [top-left, top-right, bottom-left,
+    // bottom-right]; robots on the middle row or column are not in any quadrant
+    pub fn quadrant_counts(&self, tick: i64) -> [usize; 4] {
+        let (mid_x, mid_y) = (self.width / 2, self.height / 2);
+        let mut counts = [0; 4];
+        for (x, y) in self.positions_at(tick) {
+            // Only an odd size has a middle line; an even one splits cleanly in two
+            if (self.width % 2 == 1 && x == mid_x) || (self.height % 2 == 1 && y == mid_y) {
+                continue;
+            }
+            let right = x >= (self.width + 1) / 2;
+            let bottom = y >= (self.height + 1) / 2;
+            counts[(bottom as usize) << 1 | right as usize] += 1;
+        }
+        counts
+    }
+
+    // Product of the quadrant counts, the puzzle's safety factor
+    pub fn safety_factor(&self, tick: i64) -> u64 {
+        self.quadrant_counts(tick)
+            .iter()
+            .map(|&count| count as u64)
+            .product()
+    }
+
+    // The biggest group of occupied tiles connected up, down, left or right after `tick`
+    // seconds, as sorted tiles. Groups do not continue across the wrapping edges, and of
+    // equally big groups the first one in reading order is returned.
+    pub fn largest_cluster(&self, tick: i64) -> Vec<(i64, i64)> {
+        let (width, height) = (self.width, self.height);
+        let mut occupied = vec![false; (width * height) as usize];
+        for (x, y) in self.positions_at(tick) {
+            occupied[(y * width + x) as usize] = true;
+        }
+
+        let mut best = Vec::new();
+        let mut stack = Vec::new();
+        for start in 0..occupied.len() {
+            if !occupied[start] {
+                continue;
+            }
+            occupied[start] = false;
+            stack.push(start as i64);
+
+            let mut cluster = Vec::new();
+            while let Some(cell) = stack.pop() {
+                let (x, y) = (cell % width, cell / width);
+                cluster.push((x, y));
+                for (nx, ny) in [(x + 1, y), (x - 1, y), (x, y + 1), (x, y - 1)] {
+                    if (0..width).contains(&nx) && (0..height).contains(&ny) {
+                        let next = ny * width + nx;
+                        if occupied[next as usize] {
+                            occupied[next as usize] = false;
+                            stack.push(next);
+                        }
+                    }
+                }
+            }
+            if cluster.len() > best.len() {
+                best = cluster;
+            }
+        }
+
+        best.sort_unstable();
+        best
+    }
+
+    // Robots per block of `block_width` x `block_height` tiles after `tick` seconds, one row
+    // of blocks per entry; blocks on the right and bottom edges may be smaller
+    pub fn heatmap(&self, tick: i64, block_width: i64, block_height: i64) -> Vec<Vec<usize>> {
+        assert!(
+            block_width > 0 && block_height > 0,
+            "blocks must not be empty"
+        );
+        let columns = (self.width + block_width - 1) / block_width;
+        let rows = (self.height + block_height - 1) / block_height;
+        let mut heatmap = vec![vec![0; columns as usize]; rows as usize];
+        for (x, y) in self.positions_at(tick) {
+            heatmap[(y / block_height) as usize][(x / block_width) as usize] += 1;
+        }
+        heatmap
+    }
+}
+
+// Shade a heatmap from ` ` (empty) to `@` (the densest block)
+pub fn render_heatmap(heatmap: &[Vec<usize>]) -> String {
+    const SHADES: &[u8] = b" .:-=+*#%@";
+    let levels = SHADES.len() - 1;
+    let max = heatmap.iter().flatten().copied().max().unwrap_or(0).max(1);
+
+    let mut picture = String::new();
+    for row in heatmap {
+        for &count in row {
+            // Round up so that even a single robot shows
+            picture.push(SHADES[(count * levels).div_ceil(max)] as char);
+        }
+        picture.push('\n');
+    }
+    picture
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Robots spread over the arena that all meet in a small square at `tick`
+    fn converging_robots(tick: i64, width: i64, height: i64) -> Vec<Robot> {
+        (0..200)
+            .map(|i| {
+                let target = (40 + i % 7, 50 + i / 7 % 6);
+                let velocity = ((i * 37 % 23) - 11, (i * 53 % 29) - 14);
+                Robot {
+                    position: (
+                        (target.0 - tick * velocity.0).rem_euclid(width),
+                        (target.1 - tick * velocity.1).rem_euclid(height),
+                    ),
+                    velocity,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_positions_at() {
+        let robots = parse_robots("p=0,4 v=3,-3\np=6,3 v=-1,-3\np=10,3 v=-1,2");
+        assert_eq!(
+            positions_at(&robots, 1, 101, 103),
+            vec![(3, 1), (5, 0), (9, 5)]
+        );
+    }
+
+    #[test]
+    fn test_position_at() {
+        let robot = Robot {
+            position: (2, 4),
+            velocity: (2, -3),
+        };
+        assert_eq!(robot.position_at(5, 11, 7), (1, 3));
+    }
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(crt(2, 3, 3, 5), Some((8, 15)));
+        assert_eq!(crt(69, 101, 12, 103), Some((8149, 10403)));
+        assert_eq!(crt(1, 4, 3, 6), Some((9, 12)));
+        assert_eq!(crt(1, 4, 2, 6), None);
+    }
+
+    #[test]
+    fn test_signal_scores() {
+        assert_eq!(Signal::Variance.score(&[1, 1, 1], 5), 0.0);
+        assert_eq!(Signal::Variance.score(&[0, 2], 5), 1.0);
+        assert_eq!(Signal::Entropy.score(&[3, 3, 3, 3], 5), 0.0);
+        assert_eq!(Signal::Entropy.score(&[0, 1, 2, 3], 5), 2.0);
+    }
+
+    #[test]
+    fn test_find_converging_robots() {
+        for signal in [Signal::Variance, Signal::Entropy] {
+            let robots = converging_robots(4321, 101, 103);
+            let detection = find_tree(&robots, 101, 103, signal).unwrap();
+            assert_eq!(detection.tick, 4321);
+            assert!(detection.confidence > 0.9);
+            assert!(!detection.frame.lines().nth(50).unwrap()[40..47].contains('.'));
+        }
+    }
+
+    #[test]
+    fn test_render() {
+        let frame = render(&[(0, 0), (2, 1), (2, 1)], 3, 2);
+        assert_eq!(frame, "1..\n..2\n");
+    }
+
+    const EXAMPLE: &str = "p=0,4 v=3,-3
+p=6,3 v=-1,-3
+p=10,3 v=-1,2
+p=2,0 v=2,-1
+p=0,0 v=1,3
+p=3,0 v=-2,-2
+p=7,6 v=-1,-3
+p=3,0 v=-1,-2
+p=9,3 v=2,3
+p=7,3 v=-1,2
+p=2,4 v=2,-3
+p=9,5 v=-3,-3";
+
+    #[test]
+    fn test_period() {
+        let simulation = Simulation::parse(EXAMPLE, 11, 7);
+        assert_eq!(simulation.axis_periods(), (11, 7));
+        assert_eq!(simulation.period(), 77);
+        assert_eq!(
+            simulation.positions_at(simulation.period()),
+            simulation.positions_at(0)
+        );
+
+        // Velocities sharing a factor with the arena size come back sooner
+        let simulation = Simulation::parse("p=0,0 v=2,3\np=1,1 v=4,0", 8, 9);
+        assert_eq!(simulation.axis_periods(), (4, 3));
+        assert_eq!(simulation.period(), 12);
+        assert_eq!(Simulation::parse("", 8, 9).period(), 1);
+    }
+
+    #[test]
+    fn test_quadrants() {
+        let simulation = Simulation::parse(EXAMPLE, 11, 7);
+        assert_eq!(simulation.quadrant_counts(100), [1, 3, 4, 1]);
+        assert_eq!(simulation.safety_factor(100), 12);
+
+        // An even arena has no middle line to drop robots on
+        let simulation = Simulation::parse("p=1,1 v=0,0\np=2,2 v=0,0\np=3,3 v=0,0", 4, 4);
+        assert_eq!(simulation.quadrant_counts(0), [1, 0, 0, 2]);
+    }
+
+    #[test]
+    fn test_largest_cluster() {
+        let simulation = Simulation::parse(EXAMPLE, 11, 7);
+        assert_eq!(
+            render(&simulation.positions_at(100), 11, 7),
+            "......2..1.\n...........\n1..........\n.11........\n.....1.....\n...12......\n.1....1....\n"
+        );
+        // Two clusters of two tiles tie; the first in reading order wins
+        assert_eq!(simulation.largest_cluster(100), vec![(1, 3), (2, 3)]);
+
+        let simulation = Simulation::parse(
+            "p=0,0 v=0,0\np=1,0 v=0,0\np=1,1 v=0,0\np=1,1 v=0,0\np=4,0 v=0,0\np=4,4 v=0,0",
+            5,
+            5,
+        );
+        assert_eq!(simulation.largest_cluster(0), vec![(0, 0), (1, 0), (1, 1)]);
+    }
+
+    #[test]
+    fn test_heatmap() {
+        let simulation = Simulation::parse(EXAMPLE, 11, 7);
+        let heatmap = simulation.heatmap(100, 4, 4);
+        assert_eq!(heatmap, vec![vec![3, 2, 1], vec![2, 4, 0]]);
+        assert_eq!(heatmap.iter().flatten().sum::<usize>(), 12);
+        assert_eq!(render_heatmap(&heatmap), "#+-\n+@ \n");
+    }
+}
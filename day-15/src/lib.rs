@@ -0,0 +1,712 @@
+use std::fmt;
+
+// Directions for robot movement
+pub const DIRECTION_LEFT: char = '<';
+pub const DIRECTION_RIGHT: char = '>';
+pub const DIRECTION_UP: char = '^';
+pub const DIRECTION_DOWN: char = 'v';
+
+// Map elements
+pub const TILE_WALL: char = '#';
+pub const TILE_BOX: char = 'O';
+pub const TILE_ROBOT: char = '@';
+pub const TILE_EMPTY: char = '.';
+
+// Wide boxes are drawn `[]`, with `=` filling boxes wider than two tiles
+pub const TILE_BOX_LEFT: char = '[';
+pub const TILE_BOX_RIGHT: char = ']';
+pub const TILE_BOX_MIDDLE: char = '=';
+
+// Direction the robot tries to move in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+impl Direction {
+    // Reads a movement character, returning `None` for anything else
+    pub fn from_char(c: char) -> Option<Self> {
+        match c {
+            DIRECTION_LEFT => Some(Direction::Left),
+            DIRECTION_RIGHT => Some(Direction::Right),
+            DIRECTION_UP => Some(Direction::Up),
+            DIRECTION_DOWN => Some(Direction::Down),
+            _ => None,
+        }
+    }
+
+    pub fn to_char(self) -> char {
+        match self {
+            Direction::Left => DIRECTION_LEFT,
+            Direction::Right => DIRECTION_RIGHT,
+            Direction::Up => DIRECTION_UP,
+            Direction::Down => DIRECTION_DOWN,
+        }
+    }
+
+    pub fn opposite(self) -> Self {
+        match self {
+            Direction::Left => Direction::Right,
+            Direction::Right => Direction::Left,
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+        }
+    }
+
+    // Offset of one step as (dx, dy)
+    pub fn delta(self) -> (isize, isize) {
+        match self {
+            Direction::Left => (-1, 0),
+            Direction::Right => (1, 0),
+            Direction::Up => (0, -1),
+            Direction::Down => (0, 1),
+        }
+    }
+}
+
+// Outcome of a single robot step
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoveEvent {
+    Blocked,       // a wall stopped the robot or the boxes in front of it
+    Moved,         // the robot moved onto an empty tile
+    Pushed(usize), // the robot moved and pushed this many boxes
+}
+
+// A box occupying `width` tiles to the right of (x, y)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Crate {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+}
+
+impl Crate {
+    // GPS coordinate, measured to the box's left edge
+    pub fn gps(&self) -> usize {
+        100 * self.y + self.x
+    }
+}
+
+// Error raised when a warehouse map cannot be parsed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    UnexpectedTile(char, usize, usize), // tile, x, y
+    UnclosedBox(usize, usize),          // `[` without a matching `]`, at x, y
+    UnevenRow(usize),                   // row y differs in width from the first row
+    MissingRobot,
+    MultipleRobots,
+    InvalidMove(char, usize, usize), // character, line and column within the moves (1-based)
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::UnexpectedTile(tile, x, y) => {
+                write!(f, "unexpected tile '{}' at x={}, y={}", tile, x, y)
+            }
+            ParseError::UnclosedBox(x, y) => write!(f, "unclosed box at x={}, y={}", x, y),
+            ParseError::UnevenRow(y) => write!(f, "row {} has a different width", y),
+            ParseError::MissingRobot => write!(f, "the map has no robot"),
+            ParseError::MultipleRobots => write!(f, "the map has more than one robot"),
+            ParseError::InvalidMove(c, line, column) => write!(
+                f,
+                "invalid move '{}' at line {}, column {} of the moves",
+                c, line, column
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+// Broken warehouse invariant found by `Warehouse::check_invariants`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InvariantViolation {
+    RobotOnWall(usize, usize),      // x, y
+    RobotOnBox(usize, usize),       // x, y
+    BoxOutOfBounds(usize),          // box index
+    BoxOnWall(usize),               // box index
+    OverlappingBoxes(usize, usize), // indices of two boxes sharing a tile
+    SplitBox(usize),                // a tile of this box is not marked as part of it
+    StrayBoxTile(usize, usize),     // x, y marked as a box tile without a box there
+}
+
+impl fmt::Display for InvariantViolation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InvariantViolation::RobotOnWall(x, y) => {
+                write!(f, "the robot is inside a wall at x={}, y={}", x, y)
+            }
+            InvariantViolation::RobotOnBox(x, y) => {
+                write!(f, "the robot is inside a box at x={}, y={}", x, y)
+            }
+            InvariantViolation::BoxOutOfBounds(index) => {
+                write!(f, "box {} is outside the map", index)
+            }
+            InvariantViolation::BoxOnWall(index) => write!(f, "box {} is inside a wall", index),
+            InvariantViolation::OverlappingBoxes(a, b) => {
+                write!(f, "boxes {} and {} overlap", a, b)
+            }
+            InvariantViolation::SplitBox(index) => write!(f, "box {} is split", index),
+            InvariantViolation::StrayBoxTile(x, y) => {
+                write!(f, "stray box tile without a box at x={}, y={}", x, y)
+            }
+        }
+    }
+}
+
+impl std::error::Error for InvariantViolation {}
+
+// One recorded step, enough to undo it
+#[derive(Debug, Clone)]
+struct StepRecord {
+    direction: Direction,
+    robot: (usize, usize), // robot position before the step
+    pushed: Vec<usize>,    // indices of the boxes that moved
+    event: MoveEvent,
+}
+
+// The warehouse simulator: walls, boxes of any width and the robot, with an undo history
+#[derive(Debug, Clone)]
+pub struct Warehouse {
+    map_width: usize,
+    map_height: usize,
+    walls: Vec<bool>,              // walls[y * map_width + x]
+    boxes: Vec<Crate>,             // every box, by index
+    occupancy: Vec<Option<usize>>, // index of the box covering each tile
+    robot: (usize, usize),
+    history: Vec<StepRecord>,
+}
+
+impl Warehouse {
+    // Parse the map from a textual representation. `O` is a one-tile box and `[`...`]` a
+    // wide box, with `=` filling boxes wider than two tiles
+    pub fn parse(map: &str) -> Result<Self, ParseError> {
+        let rows = map.trim().lines().map(str::trim_end).collect::<Vec<_>>();
+        let map_width = rows.first().map_or(0, |row| row.chars().count());
+        let map_height = rows.len();
+        let mut walls = vec![false; map_width * map_height];
+        let mut boxes = Vec::new();
+        let mut robot = None;
+
+        for (y, row) in rows.iter().enumerate() {
+            let tiles = row.chars().collect::<Vec<_>>();
+            if tiles.len() != map_width {
+                return Err(ParseError::UnevenRow(y));
+            }
+
+            let mut x = 0;
+            while x < tiles.len() {
+                match tiles[x] {
+                    TILE_WALL => walls[y * map_width + x] = true,
+                    TILE_EMPTY => {}
+                    TILE_ROBOT if robot.is_some() => return Err(ParseError::MultipleRobots),
+                    TILE_ROBOT => robot = Some((x, y)),
+                    TILE_BOX => boxes.push(Crate { x, y, width: 1 }),
+                    TILE_BOX_LEFT => {
+                        let middle = tiles[x + 1..]
+                            .iter()
+                            .take_while(|&&tile| tile == TILE_BOX_MIDDLE)
+                            .count();
+                        let right = x + 1 + middle;
+                        if tiles.get(right) != Some(&TILE_BOX_RIGHT) {
+                            return Err(ParseError::UnclosedBox(x, y));
+                        }
+                        boxes.push(Crate {
+                            x,
+                            y,
+                            width: right - x + 1,
+                        });
+                        x = right;
+                    }
+                    tile => return Err(ParseError::UnexpectedTile(tile, x, y)),
+                }
+                x += 1;
+            }
+        }
+
+        let robot = robot.ok_or(ParseError::MissingRobot)?;
+        Ok(Warehouse::from_parts(
+            map_width, map_height, walls, boxes, robot,
+        ))
+    }
+
+    // Assemble a warehouse, filling in which box covers each tile
+    fn from_parts(
+        map_width: usize,
+        map_height: usize,
+        walls: Vec<bool>,
+        boxes: Vec<Crate>,
+        robot: (usize, usize),
+    ) -> Warehouse {
+        let mut occupancy = vec![None; map_width * map_height];
+        for (index, b) in boxes.iter().enumerate() {
+            for x in b.x..b.x + b.width {
+                occupancy[b.y * map_width + x] = Some(index);
+            }
+        }
+
+        Warehouse {
+            map_width,
+            map_height,
+            walls,
+            boxes,
+            occupancy,
+            robot,
+            history: Vec::new(),
+        }
+    }
+
+    // Stretch the map width by repeating each tile `factor` times. Boxes become `factor`
+    // times wider and the robot keeps to the left of its stretched tile
+    pub fn widen(&self, factor: usize) -> Warehouse {
+        let map_width = self.map_width * factor;
+        let walls = self
+            .walls
+            .iter()
+            .flat_map(|&wall| std::iter::repeat_n(wall, factor))
+            .collect();
+        let boxes = self
+            .boxes
+            .iter()
+            .map(|b| Crate {
+                x: b.x * factor,
+                y: b.y,
+                width: b.width * factor,
+            })
+            .collect();
+        let robot = (self.robot.0 * factor, self.robot.1);
+
+        Warehouse::from_parts(map_width, self.map_height, walls, boxes, robot)
+    }
+
+    pub fn width(&self) -> usize {
+        self.map_width
+    }
+
+    pub fn height(&self) -> usize {
+        self.map_height
+    }
+
+    pub fn robot(&self) -> (usize, usize) {
+        self.robot
+    }
+
+    pub fn boxes(&self) -> &[Crate] {
+        &self.boxes
+    }
+
+    // Number of steps that can be undone
+    pub fn history_len(&self) -> usize {
+        self.history.len()
+    }
+
+    // Sum of the GPS coordinates of every box
+    pub fn gps_sum(&self) -> usize {
+        self.boxes.iter().map(Crate::gps).sum()
+    }
+
+    // Position one step away in a direction, or `None` if it leaves the map
+    fn neighbor(&self, (x, y): (usize, usize), direction: Direction) -> Option<(usize, usize)> {
+        let (dx, dy) = direction.delta();
+        let next = (x.checked_add_signed(dx)?, y.checked_add_signed(dy)?);
+        (next.0 < self.map_width && next.1 < self.map_height).then_some(next)
+    }
+
+    fn is_wall(&self, (x, y): (usize, usize)) -> bool {
+        self.walls[y * self.map_width + x]
+    }
+
+    fn box_at(&self, (x, y): (usize, usize)) -> Option<usize> {
+        self.occupancy[y * self.map_width + x]
+    }
+
+    // Tiles covered by a box
+    fn box_tiles(&self, index: usize) -> impl Iterator<Item = (usize, usize)> + use<> {
+        let b = self.boxes[index];
+        (b.x..b.x + b.width).map(move |x| (x, b.y))
+    }
+
+    // Collect all boxes the robot would push, or `None` if a wall blocks any of them
+    fn boxes_collect(&self, start: (usize, usize), direction: Direction) -> Option<Vec<usize>> {
+        let mut collected = Vec::new();
+        let mut seen = vec![false; self.boxes.len()];
+        if let Some(index) = self.box_at(start) {
+            seen[index] = true;
+            collected.push(index);
+        }
+
+        let mut next = 0;
+        while let Some(&index) = collected.get(next) {
+            next += 1;
+            for tile in self.box_tiles(index) {
+                let ahead = self.neighbor(tile, direction)?;
+                if self.is_wall(ahead) {
+                    return None; // A box cannot move into a wall
+                }
+                if let Some(other) = self.box_at(ahead).filter(|&other| !seen[other]) {
+                    seen[other] = true;
+                    collected.push(other);
+                }
+            }
+        }
+
+        Some(collected)
+    }
+
+    // Shift boxes one tile, clearing all old tiles before filling the new ones
+    fn boxes_move(&mut self, indices: &[usize], direction: Direction) {
+        let (dx, dy) = direction.delta();
+        for &index in indices {
+            for (x, y) in self.box_tiles(index) {
+                self.occupancy[y * self.map_width + x] = None;
+            }
+        }
+        for &index in indices {
+            let b = &mut self.boxes[index];
+            b.x = b.x.wrapping_add_signed(dx);
+            b.y = b.y.wrapping_add_signed(dy);
+            for (x, y) in self.box_tiles(index) {
+                self.occupancy[y * self.map_width + x] = Some(index);
+            }
+        }
+    }
+
+    // Move the robot one step, pushing any boxes in the way, and record it for undo
+    pub fn step(&mut self, direction: Direction) -> MoveEvent {
+        let robot = self.robot;
+        let target = self
+            .neighbor(robot, direction)
+            .filter(|&target| !self.is_wall(target));
+        let pushed = target.and_then(|target| self.boxes_collect(target, direction));
+
+        let event = match (target, &pushed) {
+            (Some(target), Some(pushed)) => {
+                self.boxes_move(pushed, direction);
+                self.robot = target;
+                match pushed.len() {
+                    0 => MoveEvent::Moved,
+                    count => MoveEvent::Pushed(count),
+                }
+            }
+            _ => MoveEvent::Blocked,
+        };
+
+        self.history.push(StepRecord {
+            direction,
+            robot,
+            pushed: pushed.unwrap_or_default(),
+            event,
+        });
+
+        #[cfg(debug_assertions)]
+        self.assert_invariants(self.history.len() - 1);
+
+        event
+    }
+
+    // Check that every box covers exactly its own tiles inside the map and away from walls,
+    // and that the robot stands on an empty tile
+    pub fn check_invariants(&self) -> Result<(), InvariantViolation> {
+        let mut box_tiles = 0;
+        for (index, b) in self.boxes.iter().enumerate() {
+            if b.y >= self.map_height || b.width == 0 || b.x + b.width > self.map_width {
+                return Err(InvariantViolation::BoxOutOfBounds(index));
+            }
+            for tile in self.box_tiles(index) {
+                if self.is_wall(tile) {
+                    return Err(InvariantViolation::BoxOnWall(index));
+                }
+                match self.box_at(tile) {
+                    Some(owner) if owner == index => {}
+                    Some(owner) if self.box_tiles(owner).any(|other| other == tile) => {
+                        return Err(InvariantViolation::OverlappingBoxes(owner, index));
+                    }
+                    _ => return Err(InvariantViolation::SplitBox(index)),
+                }
+            }
+            box_tiles += b.width;
+        }
+
+        // Every marked tile belongs to its box, so any extra marked tile is a stray
+        if self.occupancy.iter().flatten().count() != box_tiles {
+            let cell = (0..self.occupancy.len())
+                .find(|&cell| {
+                    self.occupancy[cell].is_some_and(|owner| {
+                        let (x, y) = (cell % self.map_width, cell / self.map_width);
+                        !self.box_tiles(owner).any(|tile| tile == (x, y))
+                    })
+                })
+                .unwrap();
+            let (x, y) = (cell % self.map_width, cell / self.map_width);
+            return Err(InvariantViolation::StrayBoxTile(x, y));
+        }
+
+        let (x, y) = self.robot;
+        if x >= self.map_width || y >= self.map_height || self.is_wall(self.robot) {
+            return Err(InvariantViolation::RobotOnWall(x, y));
+        }
+        if self.box_at(self.robot).is_some() {
+            return Err(InvariantViolation::RobotOnBox(x, y));
+        }
+        Ok(())
+    }
+
+    // Panic with the map and the offending move index if an invariant is broken. Runs
+    // after every step in debug builds so regressions fail loudly
+    pub fn assert_invariants(&self, move_index: usize) {
+        if let Err(violation) = self.check_invariants() {
+            let mut map = String::new();
+            self.write_map(&mut map)
+                .expect("writing to a String cannot fail");
+            let direction = self
+                .history
+                .get(move_index)
+                .map_or('?', |r| r.direction.to_char());
+            panic!(
+                "invariant violated after move {} ('{}'): {}\n{}",
+                move_index, direction, violation, map
+            );
+        }
+    }
+
+    // Undo the most recent step, returning its event, or `None` if there is no history
+    pub fn undo(&mut self) -> Option<MoveEvent> {
+        let record = self.history.pop()?;
+        if record.event != MoveEvent::Blocked {
+            self.boxes_move(&record.pushed, record.direction.opposite());
+            self.robot = record.robot;
+        }
+        Some(record.event)
+    }
+
+    // Character drawn for a tile
+    fn tile(&self, (x, y): (usize, usize)) -> char {
+        if (x, y) == self.robot {
+            return TILE_ROBOT;
+        }
+        if self.is_wall((x, y)) {
+            return TILE_WALL;
+        }
+        match self.box_at((x, y)).map(|index| self.boxes[index]) {
+            None => TILE_EMPTY,
+            Some(b) if b.width == 1 => TILE_BOX,
+            Some(b) if x == b.x => TILE_BOX_LEFT,
+            Some(b) if x == b.x + b.width - 1 => TILE_BOX_RIGHT,
+            Some(_) => TILE_BOX_MIDDLE,
+        }
+    }
+
+    // Write the current state of the map, one row per line
+    pub fn write_map(&self, out: &mut impl fmt::Write) -> fmt::Result {
+        for y in 0..self.map_height {
+            for x in 0..self.map_width {
+                out.write_char(self.tile((x, y)))?;
+            }
+            out.write_char('\n')?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Warehouse {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.write_map(f)
+    }
+}
+
+// Reads the movement instructions, skipping whitespace and line breaks. Any other
+// character is an error reporting its line and column within the moves
+pub fn parse_moves(text: &str) -> Result<Vec<Direction>, ParseError> {
+    let mut moves = Vec::with_capacity(text.len());
+    for (line_no, line) in text.lines().enumerate() {
+        for (col_no, c) in line.chars().enumerate() {
+            match Direction::from_char(c) {
+                Some(direction) => moves.push(direction),
+                None if c.is_whitespace() => {}
+                None => return Err(ParseError::InvalidMove(c, line_no + 1, col_no + 1)),
+            }
+        }
+    }
+    Ok(moves)
+}
+
+// Splits the input at the first blank line into the warehouse map and the moves
+pub fn get_simulation_input(input: &str) -> Result<(Warehouse, Vec<Direction>), ParseError> {
+    let input = input.trim();
+    let (map, moves) = input.split_once("\n\n").unwrap_or((input, ""));
+
+    Ok((Warehouse::parse(map)?, parse_moves(moves.trim())?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SMALL_WIDE: &str = "#######
+#...#.#
+#.....#
+#..OO@#
+#..O..#
+#.....#
+#######
+
+<vv<<^^<<^^";
+
+    #[test]
+    fn test_wide_pushes() {
+        let (warehouse, moves) = get_simulation_input(SMALL_WIDE).unwrap();
+        let mut wide = warehouse.widen(2);
+        assert_eq!(
+            wide.to_string(),
+            "##############\n##......##..##\n##..........##\n##....[][]@.##\n##....[]....##\n##..........##\n##############\n"
+        );
+
+        let events = moves.iter().map(|&m| wide.step(m)).collect::<Vec<_>>();
+        assert_eq!(events[0], MoveEvent::Pushed(2));
+        assert_eq!(events[1], MoveEvent::Moved);
+        assert_eq!(
+            wide.to_string(),
+            "##############\n##...[].##..##\n##...@.[]...##\n##....[]....##\n##..........##\n##..........##\n##############\n"
+        );
+        assert_eq!(wide.history_len(), moves.len());
+    }
+
+    #[test]
+    fn test_undo() {
+        let (warehouse, moves) = get_simulation_input(SMALL_WIDE).unwrap();
+        let mut wide = warehouse.widen(2);
+        let initial = wide.to_string();
+
+        for &direction in &moves {
+            wide.step(direction);
+        }
+        let mut undone = 0;
+        while wide.undo().is_some() {
+            undone += 1;
+        }
+        assert_eq!(undone, moves.len());
+        assert_eq!(wide.to_string(), initial);
+        assert_eq!(wide.undo(), None);
+    }
+
+    #[test]
+    fn test_arbitrary_widths() {
+        let mut warehouse = Warehouse::parse("#########\n#@[=]O..#\n#########").unwrap();
+        assert_eq!(warehouse.boxes()[0].width, 3);
+        assert_eq!(warehouse.step(Direction::Right), MoveEvent::Pushed(2));
+        assert_eq!(warehouse.step(Direction::Right), MoveEvent::Pushed(2));
+        assert_eq!(warehouse.step(Direction::Right), MoveEvent::Blocked);
+        assert_eq!(warehouse.to_string(), "#########\n#..@[=]O#\n#########\n");
+
+        let triple = Warehouse::parse("#####\n#@O.#\n#####").unwrap().widen(3);
+        assert_eq!(
+            triple.to_string(),
+            "###############\n###@..[=]...###\n###############\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_moves() {
+        assert_eq!(
+            parse_moves("<^\nv>\n"),
+            Ok(vec![
+                Direction::Left,
+                Direction::Up,
+                Direction::Down,
+                Direction::Right
+            ])
+        );
+        assert_eq!(
+            parse_moves("<<^^\nv>x<"),
+            Err(ParseError::InvalidMove('x', 2, 3))
+        );
+        assert_eq!(
+            get_simulation_input("#@.#\n\n>>\n>A").unwrap_err(),
+            ParseError::InvalidMove('A', 2, 2)
+        );
+    }
+
+    #[test]
+    fn test_invariants() {
+        let (warehouse, moves) = get_simulation_input(SMALL_WIDE).unwrap();
+        let mut wide = warehouse.widen(2);
+        assert_eq!(wide.check_invariants(), Ok(()));
+        for &direction in &moves {
+            wide.step(direction);
+            assert_eq!(wide.check_invariants(), Ok(()));
+        }
+
+        // Losing half of a wide box leaves a lone `[` on the map
+        let mut broken = wide.clone();
+        let b = broken.boxes[0];
+        broken.occupancy[b.y * broken.map_width + b.x + 1] = None;
+        assert_eq!(
+            broken.check_invariants(),
+            Err(InvariantViolation::SplitBox(0))
+        );
+        assert!(broken.to_string().contains("[."));
+
+        let mut broken = wide.clone();
+        broken.boxes[1] = broken.boxes[0];
+        assert_eq!(
+            broken.check_invariants(),
+            Err(InvariantViolation::OverlappingBoxes(0, 1))
+        );
+
+        let mut broken = wide.clone();
+        broken.occupancy[broken.map_width + 2] = Some(0);
+        assert_eq!(
+            broken.check_invariants(),
+            Err(InvariantViolation::StrayBoxTile(2, 1))
+        );
+
+        let mut broken = wide.clone();
+        broken.robot = (b.x, b.y);
+        assert_eq!(
+            broken.check_invariants(),
+            Err(InvariantViolation::RobotOnBox(b.x, b.y))
+        );
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "invariant violated after move 2 ('<'): box 0 is split\n####")]
+    fn test_invariant_checked_after_step() {
+        let (warehouse, _) = get_simulation_input(SMALL_WIDE).unwrap();
+        let mut wide = warehouse.widen(2);
+        wide.step(Direction::Up);
+        wide.step(Direction::Up);
+
+        let b = wide.boxes[0];
+        wide.occupancy[b.y * wide.map_width + b.x + 1] = None;
+        wide.step(Direction::Left);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            Warehouse::parse("#[.#\n#@.#").unwrap_err(),
+            ParseError::UnclosedBox(1, 0)
+        );
+        assert_eq!(
+            Warehouse::parse("#..#").unwrap_err(),
+            ParseError::MissingRobot
+        );
+        assert_eq!(
+            Warehouse::parse("#@@#").unwrap_err(),
+            ParseError::MultipleRobots
+        );
+        assert_eq!(
+            Warehouse::parse("#@x#").unwrap_err(),
+            ParseError::UnexpectedTile('x', 2, 0)
+        );
+        assert_eq!(
+            Warehouse::parse("#@.#\n#.#").unwrap_err(),
+            ParseError::UnevenRow(1)
+        );
+    }
+}
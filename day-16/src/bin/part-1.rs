@@ -1,63 +1,5 @@
-use std::{
-    collections::{HashMap, HashSet},
-    error::Error,
-};
-
-// Struct to represent the reindeer, including its curr position, direction, score, and prev steps
-#[derive(Debug, Clone)]
-struct Map {
-    position: (usize, usize),  // curr position of the reindeer
-    facing: Direction,         // curr direction the reindeer is facing
-    score: usize,              // curr score of the reindeer (based on distance traveled)
-    prev: Vec<(usize, usize)>, // List of prev positions (steps taken)
-}
-
-impl Map {
-    fn new() -> Self {
-        Map {
-            position: (0, 0),
-            facing: Direction::East,
-            score: 0,
-            prev: vec![],
-        }
-    }
-
-    // Returns a new Map instance with updated position and direction
-    fn next_step(&self, new_pos: Option<(usize, usize)>, new_direction: Option<Direction>) -> Self {
-        let mut score = self.score;
-        let mut pos = self.position;
-        let mut face = self.facing;
-        let mut prev = self.prev.clone();
-
-        // If a new position is provided, update the position and increase score
-        if let Some(position) = new_pos {
-            pos = position;
-            prev.push(self.position);
-            score += 1;
-        }
-
-        // If a new direction is provided, update the direction and increase score
-        if let Some(dir) = new_direction {
-            face = dir;
-            score += 1_000;
-        }
-
-        Map {
-            position: pos,
-            facing: face,
-            score,
-            prev,
-        }
-    }
-}
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-enum Direction {
-    North,
-    East,
-    South,
-    West,
-}
+use day_16::{BestPaths, Costs, Maze, ParseError, RouteDiff, k_best_routes};
+use std::error::Error;
 
 // Collect the command-line argument for input file
 fn get_arg_input() -> String {
@@ -71,113 +13,90 @@ fn get_arg_input() -> String {
 }
 
 // Parse input to identify walls, start position, and end position
-fn parse_input(input: &str) -> (HashSet<(usize, usize)>, Map, (usize, usize)) {
-    let mut walls = HashSet::new();
-    let mut start = Map::new();
-    let mut end = (0, 0);
-    for (line_no, line) in input.trim().lines().enumerate() {
-        for (col_no, elem) in line.char_indices() {
-            match elem {
-                '#' => {
-                    walls.insert((line_no, col_no)); // Mark walls
-                }
-                'S' => {
-                    start.position = (line_no, col_no); // Mark start position
-                }
-                'E' => {
-                    end = (line_no, col_no); // Mark end position
-                }
-                '.' => {}
-                _ => unreachable!(),
-            }
-        }
-    }
-    (walls, start, end)
+fn parse_input(input: &str) -> Result<Maze, ParseError> {
+    Maze::parse(input)
 }
 
 // Solves the maze and calculates the lowest score to reach the end
-fn solve(data: (HashSet<(usize, usize)>, Map, (usize, usize))) -> Result<usize, Box<dyn Error>> {
-    let (walls, start_map, end_pos) = data;
-    let mut queue = vec![start_map];
-    let mut reached: HashMap<(usize, usize, Direction), usize> = HashMap::new();
-
-    while let Some(curr) = queue.pop() {
-        // If the current position matches the end, return the score
-        if curr.position == end_pos {
-            println!("The lowest score the map can get is: {}", curr.score);
-            return Ok(curr.score);
-        }
+fn solve(maze: &Maze, costs: Costs) -> Result<usize, Box<dyn Error>> {
+    let best = BestPaths::find(maze, costs).ok_or("No path found!")?;
 
-        // Skip positions that have already been reached with a lower score
-        let prev = reached
-            .entry((curr.position.0, curr.position.1, curr.facing))
-            .or_insert(usize::MAX);
-
-        if *prev < curr.score {
-            continue;
-        } else {
-            *prev = curr.score;
-        }
+    Ok(best.score())
+}
 
-        // Get the next possible positions and directions
-        let (forward_pos, left_neighbor, right_neighbor) = match curr.facing {
-            Direction::North => (
-                (curr.position.0 - 1, curr.position.1),
-                (curr.position.0, curr.position.1 - 1, Direction::West),
-                (curr.position.0, curr.position.1 + 1, Direction::East),
-            ),
-            Direction::East => (
-                (curr.position.0, curr.position.1 + 1),
-                (curr.position.0 - 1, curr.position.1, Direction::North),
-                (curr.position.0 + 1, curr.position.1, Direction::South),
-            ),
-            Direction::South => (
-                (curr.position.0 + 1, curr.position.1),
-                (curr.position.0, curr.position.1 + 1, Direction::East),
-                (curr.position.0, curr.position.1 - 1, Direction::West),
-            ),
-            Direction::West => (
-                (curr.position.0, curr.position.1 - 1),
-                (curr.position.0 + 1, curr.position.1, Direction::South),
-                (curr.position.0 - 1, curr.position.1, Direction::North),
-            ),
-        };
+// Lists the `k` best distinct routes with their scores and the tiles they cover
+fn describe_routes(maze: &Maze, costs: Costs, k: usize) -> String {
+    k_best_routes(maze, costs, k)
+        .iter()
+        .enumerate()
+        .map(|(index, route)| {
+            format!(
+                "route {}: score {}, {} tiles\n",
+                index + 1,
+                route.score,
+                route.tiles().len()
+            )
+        })
+        .collect()
+}
 
-        let mut added = false;
+// Shows where the `first` and `second` best routes (counting from 1) diverge
+fn describe_diff(
+    maze: &Maze,
+    costs: Costs,
+    first: usize,
+    second: usize,
+) -> Result<String, Box<dyn Error>> {
+    let routes = k_best_routes(maze, costs, first.max(second));
+    let route = |number: usize| {
+        number
+            .checked_sub(1)
+            .and_then(|index| routes.get(index))
+            .ok_or_else(|| format!("there is no route {}", number))
+    };
+    let diff = RouteDiff::new(route(first)?, route(second)?);
+
+    Ok(format!("{}\n{}", diff.summary(), maze.render_diff(&diff)))
+}
 
-        // Add valid steps to the queue
-        if !walls.contains(&forward_pos) {
-            queue.push(curr.next_step(Some(forward_pos), None));
-            added = true;
-        }
-        if !walls.contains(&(left_neighbor.0, left_neighbor.1)) {
-            queue.push(curr.next_step(None, Some(left_neighbor.2)));
-            added = true;
+// Reads `[step_cost] [turn_cost] [--routes K | --diff I J]` and produces the requested output
+fn run(maze: &Maze, args: &[String]) -> Result<String, Box<dyn Error>> {
+    let number = |arg: Option<&String>| -> Result<usize, Box<dyn Error>> {
+        let arg = arg.ok_or("missing route number")?;
+        arg.parse()
+            .map_err(|_| format!("invalid route number '{}'", arg).into())
+    };
+
+    match args
+        .iter()
+        .position(|arg| arg == "--routes" || arg == "--diff")
+    {
+        Some(flag) if args[flag] == "--routes" => {
+            let costs = Costs::parse(&args[..flag])?;
+            Ok(describe_routes(maze, costs, number(args.get(flag + 1))?))
         }
-        if !walls.contains(&(right_neighbor.0, right_neighbor.1)) {
-            queue.push(curr.next_step(None, Some(right_neighbor.2)));
-            added = true;
+        Some(flag) => {
+            let costs = Costs::parse(&args[..flag])?;
+            let (first, second) = (number(args.get(flag + 1))?, number(args.get(flag + 2))?);
+            describe_diff(maze, costs, first, second)
         }
-
-        // If no new positions were added, return early
-        if !added && queue.is_empty() {
-            return Err("No path found!".into());
-        }
-
-        // Sort the queue by score (higher score first)
-        queue.sort_by(|a, b| b.score.cmp(&a.score));
+        None => Ok(format!("result: {}\n", solve(maze, Costs::parse(args)?)?)),
     }
-
-    Err("No path found!".into())
 }
 
 fn main() {
     let input = get_arg_input();
-    let data = parse_input(&input);
-
-    match solve(data) {
-        Ok(score) => println!("result: {}", score),
-        Err(e) => eprintln!("error: {}", e),
+    let args: Vec<String> = std::env::args().skip(2).collect(); // Options after the input file
+
+    match parse_input(&input)
+        .map_err(Box::<dyn Error>::from)
+        .and_then(|maze| run(&maze, &args))
+    {
+        Ok(output) => print!("{}", output),
+        Err(e) => {
+            eprintln!("error: {}", e);
+            std::process::exit(1);
+        }
     }
 }
 
@@ -187,12 +106,6 @@ mod tests {
 
     // Collect the content using input file name
     fn get_file_input(file_path: &str) -> String {
-        let args: Vec<String> = std::env::args().collect();
-        if args.len() < 2 {
-            eprintln!("Usage: {} <input_file>", args[0]);
-            std::process::exit(1);
-        }
-
         std::fs::read_to_string(file_path).expect("Failed to read input file")
     }
 
@@ -216,17 +129,33 @@ mod tests {
 ###############
 "#;
 
-        let data = parse_input(input);
-        let result = solve(data).unwrap();
+        let maze = parse_input(input).unwrap();
+        let result = solve(&maze, Costs::default()).unwrap();
         assert_eq!(result, 7036);
+
+        let cheap_turns = Costs { step: 1, turn: 0 };
+        assert_eq!(solve(&maze, cheap_turns).unwrap(), 28);
+
+        let args = |text: &str| {
+            text.split_whitespace()
+                .map(String::from)
+                .collect::<Vec<_>>()
+        };
+        let routes = run(&maze, &args("--routes 4")).unwrap();
+        assert!(routes.starts_with("route 1: score 7036, "));
+        assert_eq!(routes.lines().count(), 4);
+
+        let diff = run(&maze, &args("--diff 1 2")).unwrap();
+        assert!(diff.starts_with("the routes split at "));
+        assert!(run(&maze, &args("--diff 1 0")).is_err());
     }
 
     #[test]
     fn test_with_input() {
         let file_path = "docs/challenge_1.txt";
         let input = get_file_input(file_path);
-        let data = parse_input(&input);
-        let result = solve(data).unwrap();
+        let maze = parse_input(&input).unwrap();
+        let result = solve(&maze, Costs::default()).unwrap();
         assert_eq!(result, 95476);
     }
 }
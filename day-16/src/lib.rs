@@ -0,0 +1,832 @@
+use std::{
+    cmp::Reverse,
+    collections::{BTreeSet, BinaryHeap, HashSet},
+    fmt,
+};
+
+// Type alias for representing a tile of the maze as (row, column)
+pub type Position = (usize, usize);
+
+// Type alias for representing a node of the state graph: where the reindeer is and where it faces
+pub type State = (Position, Direction);
+
+// Enum to represent the possible movement directions of the reindeer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [
+        Direction::North,
+        Direction::East,
+        Direction::South,
+        Direction::West,
+    ];
+
+    // Direction after rotating 90 degrees clockwise
+    pub fn clockwise(self) -> Self {
+        Direction::ALL[(self.index() + 1) % 4]
+    }
+
+    // Direction after rotating 90 degrees counterclockwise
+    pub fn counterclockwise(self) -> Self {
+        Direction::ALL[(self.index() + 3) % 4]
+    }
+
+    // Direction after turning around
+    pub fn opposite(self) -> Self {
+        Direction::ALL[(self.index() + 2) % 4]
+    }
+
+    // Returns the neighbouring position in this direction, if it does not leave the grid
+    pub fn step(self, (row, col): Position) -> Option<Position> {
+        match self {
+            Direction::North => Some((row.checked_sub(1)?, col)),
+            Direction::East => Some((row, col + 1)),
+            Direction::South => Some((row + 1, col)),
+            Direction::West => Some((row, col.checked_sub(1)?)),
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+// Scoring of the reindeer's moves: the puzzle charges 1 per step and 1000 per turn
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Costs {
+    pub step: usize, // cost of moving one tile forward
+    pub turn: usize, // cost of rotating 90 degrees in place
+}
+
+impl Costs {
+    // Parses `[step] [turn]` costs, keeping the puzzle's cost for any that are missing
+    pub fn parse(args: &[String]) -> Result<Self, String> {
+        let mut costs = Costs::default();
+        let mut values = args.iter().map(|arg| {
+            arg.parse::<usize>()
+                .map_err(|_| format!("invalid cost '{}'", arg))
+        });
+        if let Some(step) = values.next() {
+            costs.step = step?;
+        }
+        if let Some(turn) = values.next() {
+            costs.turn = turn?;
+        }
+        if values.next().is_some() {
+            return Err("expected at most a step cost and a turn cost".to_string());
+        }
+        Ok(costs)
+    }
+}
+
+impl Default for Costs {
+    fn default() -> Self {
+        Costs {
+            step: 1,
+            turn: 1_000,
+        }
+    }
+}
+
+// Error raised when the maze text cannot be parsed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    UnexpectedTile(char, Position), // a character other than `#`, `.`, `O`, `S` or `E`
+    MissingStart,                   // no `S` tile
+    MissingEnd,                     // no `E` tile
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::UnexpectedTile(tile, (row, col)) => {
+                write!(
+                    f,
+                    "unexpected tile '{}' at row {}, column {}",
+                    tile, row, col
+                )
+            }
+            ParseError::MissingStart => write!(f, "the maze has no start tile 'S'"),
+            ParseError::MissingEnd => write!(f, "the maze has no end tile 'E'"),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+// The maze: walls on a grid, the reindeer's start tile (facing East) and the end tile
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Maze {
+    walls: Vec<Vec<bool>>, // walls[row][col] is true for `#`
+    width: usize,          // length of the longest row
+    start: Position,
+    end: Position,
+}
+
+impl Maze {
+    // Parses the maze, ignoring surrounding blank lines. `O` marks are read as open tiles
+    pub fn parse(input: &str) -> Result<Self, ParseError> {
+        let mut walls = Vec::new();
+        let mut start = None;
+        let mut end = None;
+
+        for (row, line) in input.trim().lines().enumerate() {
+            let mut wall_row = Vec::with_capacity(line.len());
+            for (col, tile) in line.trim_end().chars().enumerate() {
+                match tile {
+                    '#' => {}
+                    'S' => start = Some((row, col)),
+                    'E' => end = Some((row, col)),
+                    '.' | 'O' => {}
+                    _ => return Err(ParseError::UnexpectedTile(tile, (row, col))),
+                }
+                wall_row.push(tile == '#');
+            }
+            walls.push(wall_row);
+        }
+
+        Ok(Maze {
+            width: walls.iter().map(Vec::len).max().unwrap_or(0),
+            walls,
+            start: start.ok_or(ParseError::MissingStart)?,
+            end: end.ok_or(ParseError::MissingEnd)?,
+        })
+    }
+
+    pub fn start(&self) -> Position {
+        self.start
+    }
+
+    pub fn end(&self) -> Position {
+        self.end
+    }
+
+    // The reindeer starts on the start tile facing East
+    pub fn start_state(&self) -> State {
+        (self.start, Direction::East)
+    }
+
+    pub fn height(&self) -> usize {
+        self.walls.len()
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    // Checks whether a position is inside the maze and not a wall
+    pub fn is_open(&self, (row, col): Position) -> bool {
+        self.walls
+            .get(row)
+            .and_then(|line| line.get(col))
+            .is_some_and(|&wall| !wall)
+    }
+
+    // Lists the states reachable in one move with their costs: a step forward onto an
+    // open tile, or a quarter turn either way
+    pub fn successors(&self, (position, facing): State, costs: Costs) -> Vec<(State, usize)> {
+        let mut next = Vec::with_capacity(3);
+        if let Some(forward) = facing.step(position).filter(|&pos| self.is_open(pos)) {
+            next.push(((forward, facing), costs.step));
+        }
+        next.push(((position, facing.clockwise()), costs.turn));
+        next.push(((position, facing.counterclockwise()), costs.turn));
+        next
+    }
+
+    // Flat index of a state, used for the distance and predecessor tables
+    fn state_index(&self, ((row, col), facing): State) -> usize {
+        (row * self.width + col) * 4 + facing.index()
+    }
+
+    // Draws the maze with every tile in `tiles` marked `O`, as in the puzzle text
+    pub fn render(&self, tiles: &BTreeSet<Position>) -> String {
+        let mut output = String::new();
+        for (row, line) in self.walls.iter().enumerate() {
+            for (col, &wall) in line.iter().enumerate() {
+                output.push(match (row, col) {
+                    _ if wall => '#',
+                    pos if tiles.contains(&pos) => 'O',
+                    pos if pos == self.start => 'S',
+                    pos if pos == self.end => 'E',
+                    _ => '.',
+                });
+            }
+            output.push('\n');
+        }
+        output
+    }
+}
+
+// Result of the state-graph search: the best score and the graph of best predecessors
+#[derive(Debug, Clone)]
+pub struct BestPaths {
+    score: usize,
+    end_states: Vec<State>, // states on the end tile reached with the best score
+    predecessors: Vec<Vec<State>>, // best predecessors of every state, by state index
+    tiles: BTreeSet<Position>, // tiles on at least one best path
+}
+
+impl BestPaths {
+    // Runs Dijkstra over `(Position, Direction)` states. Every predecessor reaching a state
+    // with its best distance is kept, so the predecessor graph holds all best paths. With
+    // zero-cost moves it can hold cycles too, which the walk back from the end skips over.
+    // Returns `None` if the end cannot be reached
+    pub fn find(maze: &Maze, costs: Costs) -> Option<Self> {
+        let states = maze.height() * maze.width() * 4;
+        let mut distances = vec![usize::MAX; states];
+        let mut settled = vec![false; states];
+        let mut predecessors = vec![Vec::new(); states];
+        let mut queue = BinaryHeap::new();
+        let mut score = None;
+
+        let start = maze.start_state();
+        distances[maze.state_index(start)] = 0;
+        queue.push(Reverse((0, start)));
+
+        while let Some(Reverse((distance, state))) = queue.pop() {
+            if settled[maze.state_index(state)] {
+                continue; // Stale queue entry
+            }
+            if score.is_some_and(|best| distance > best) {
+                break; // Every remaining state is worse than the best path
+            }
+            settled[maze.state_index(state)] = true;
+            if state.0 == maze.end() {
+                score = Some(distance);
+                continue;
+            }
+
+            for (next, cost) in maze.successors(state, costs) {
+                let next_distance = distance + cost;
+                let index = maze.state_index(next);
+                if next_distance < distances[index] {
+                    distances[index] = next_distance;
+                    predecessors[index] = vec![state];
+                    queue.push(Reverse((next_distance, next)));
+                } else if next_distance == distances[index] {
+                    // A zero-cost move can reach a state that is already settled; it is
+                    // still an equally good way in
+                    predecessors[index].push(state);
+                }
+            }
+        }
+
+        let score = score?;
+        let end_states = Direction::ALL
+            .iter()
+            .map(|&facing| (maze.end(), facing))
+            .filter(|&state| distances[maze.state_index(state)] == score)
+            .collect::<Vec<_>>();
+
+        // Walk the predecessor graph back from the end to collect the tiles, visiting each
+        // state once so zero-cost cycles end
+        let mut tiles = BTreeSet::new();
+        let mut visited = vec![false; states];
+        let mut to_visit = end_states.clone();
+        while let Some(state) = to_visit.pop() {
+            let index = maze.state_index(state);
+            if visited[index] {
+                continue;
+            }
+            visited[index] = true;
+            tiles.insert(state.0);
+            to_visit.extend(predecessors[index].iter().copied());
+        }
+
+        // Keep only the predecessors that lie on a best path
+        for (index, preds) in predecessors.iter_mut().enumerate() {
+            if !visited[index] {
+                preds.clear();
+            }
+        }
+
+        Some(BestPaths {
+            score,
+            end_states,
+            predecessors,
+            tiles,
+        })
+    }
+
+    // Lowest score the reindeer can get
+    pub fn score(&self) -> usize {
+        self.score
+    }
+
+    // Number of tiles that are part of at least one best path, including start and end
+    pub fn tile_count(&self) -> usize {
+        self.tiles.len()
+    }
+
+    // Tiles that are part of at least one best path
+    pub fn tiles(&self) -> &BTreeSet<Position> {
+        &self.tiles
+    }
+
+    // States on the end tile that complete a best path
+    pub fn end_states(&self) -> &[State] {
+        &self.end_states
+    }
+
+    // Predecessors of a state along the best paths (empty if the state is on none)
+    pub fn predecessors(&self, maze: &Maze, state: State) -> &[State] {
+        &self.predecessors[maze.state_index(state)]
+    }
+}
+
+// One route through the maze as the sequence of states from the start to the end tile
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Route {
+    pub score: usize,
+    pub states: Vec<State>,
+}
+
+impl Route {
+    // Tiles the route passes through
+    pub fn tiles(&self) -> BTreeSet<Position> {
+        self.states.iter().map(|&(position, _)| position).collect()
+    }
+}
+
+// Cost of a single move between two adjacent states: a step if the position changes,
+// otherwise a turn
+fn move_cost(from: State, to: State, costs: Costs) -> usize {
+    if from.0 != to.0 {
+        costs.step
+    } else {
+        costs.turn
+    }
+}
+
+// Cost of the cheapest way from every state to the end tile, found by running Dijkstra
+// backwards from the end states. Banning moves can only make routes dearer, so this is an
+// admissible A* heuristic for the spur searches
+fn distances_to_end(maze: &Maze, costs: Costs) -> Vec<usize> {
+    let mut distances = vec![usize::MAX; maze.height() * maze.width() * 4];
+    let mut queue = BinaryHeap::new();
+    for facing in Direction::ALL {
+        distances[maze.state_index((maze.end(), facing))] = 0;
+        queue.push(Reverse((0, (maze.end(), facing))));
+    }
+
+    while let Some(Reverse((distance, (position, facing)))) = queue.pop() {
+        if distance > distances[maze.state_index((position, facing))] {
+            continue; // Stale queue entry
+        }
+
+        // States that move into this one: turning from either side, or stepping from behind
+        let mut previous = vec![
+            ((position, facing.clockwise()), costs.turn),
+            ((position, facing.counterclockwise()), costs.turn),
+        ];
+        let behind = facing.opposite().step(position);
+        if let Some(behind) = behind.filter(|&pos| maze.is_open(pos)) {
+            previous.push(((behind, facing), costs.step));
+        }
+
+        for (state, cost) in previous {
+            let index = maze.state_index(state);
+            if state.0 != maze.end() && distance + cost < distances[index] {
+                distances[index] = distance + cost;
+                queue.push(Reverse((distance + cost, state)));
+            }
+        }
+    }
+
+    distances
+}
+
+// Cheapest route from `from` to the end tile that avoids the banned states and moves,
+// used for the spur searches of Yen's algorithm. Searches with A*, guided by `to_end`
+fn cheapest_route(
+    maze: &Maze,
+    costs: Costs,
+    from: State,
+    to_end: &[usize],
+    banned_states: &[bool],
+    banned_moves: &HashSet<(State, State)>,
+) -> Option<Route> {
+    let states = maze.height() * maze.width() * 4;
+    let mut distances = vec![usize::MAX; states];
+    let mut previous = vec![None; states];
+    let mut queue = BinaryHeap::from([Reverse((to_end[maze.state_index(from)], 0, from))]);
+    distances[maze.state_index(from)] = 0;
+
+    while let Some(Reverse((_, distance, state))) = queue.pop() {
+        if distance > distances[maze.state_index(state)] {
+            continue; // Stale queue entry
+        }
+        if state.0 == maze.end() {
+            let mut states = vec![state];
+            while let Some(prev) = previous[maze.state_index(*states.last().unwrap())] {
+                states.push(prev);
+            }
+            states.reverse();
+            return Some(Route {
+                score: distance,
+                states,
+            });
+        }
+
+        for (next, cost) in maze.successors(state, costs) {
+            let index = maze.state_index(next);
+            if banned_states[index]
+                || to_end[index] == usize::MAX
+                || banned_moves.contains(&(state, next))
+            {
+                continue;
+            }
+            if distance + cost < distances[index] {
+                distances[index] = distance + cost;
+                previous[index] = Some(state);
+                queue.push(Reverse((
+                    distance + cost + to_end[index],
+                    distance + cost,
+                    next,
+                )));
+            }
+        }
+    }
+
+    None
+}
+
+// Finds up to `k` best distinct routes with Yen's algorithm, cheapest first. Each new
+// route branches off an earlier one at a spur state: the shared root is kept, the moves
+// the earlier routes took from that root are banned, and the rest is searched again
+pub fn k_best_routes(maze: &Maze, costs: Costs, k: usize) -> Vec<Route> {
+    let to_end = distances_to_end(maze, costs);
+    let no_states = vec![false; maze.height() * maze.width() * 4];
+    let start = maze.start_state();
+    let Some(best) = cheapest_route(maze, costs, start, &to_end, &no_states, &HashSet::new())
+    else {
+        return Vec::new();
+    };
+
+    let mut routes = vec![best];
+    let mut candidates = BinaryHeap::new();
+    let mut seen = HashSet::from([routes[0].states.clone()]);
+
+    while routes.len() < k {
+        let last = routes.last().unwrap().clone();
+        let mut banned_states = no_states.clone();
+        let mut root_score = 0;
+
+        for spur_index in 0..last.states.len() - 1 {
+            let spur = last.states[spur_index];
+            let root = &last.states[..=spur_index];
+
+            // Ban the next move of every route sharing this root
+            let banned_moves = routes
+                .iter()
+                .filter(|route| route.states.len() > spur_index + 1)
+                .filter(|route| route.states[..=spur_index] == *root)
+                .map(|route| (spur, route.states[spur_index + 1]))
+                .collect::<HashSet<_>>();
+
+            if let Some(spur_route) =
+                cheapest_route(maze, costs, spur, &to_end, &banned_states, &banned_moves)
+            {
+                let mut states = root[..spur_index].to_vec();
+                states.extend(spur_route.states);
+                if seen.insert(states.clone()) {
+                    candidates.push(Reverse(Route {
+                        score: root_score + spur_route.score,
+                        states,
+                    }));
+                }
+            }
+
+            // The root may not loop back through its own states
+            banned_states[maze.state_index(spur)] = true;
+            root_score += move_cost(spur, last.states[spur_index + 1], costs);
+        }
+
+        match candidates.pop() {
+            Some(Reverse(route)) => routes.push(route),
+            None => break, // No other loopless routes exist
+        }
+    }
+
+    routes
+}
+
+// Where two routes agree and where they part ways
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RouteDiff {
+    pub split: Option<State>, // last shared state before the routes diverge (None if identical)
+    pub rejoin: Option<State>, // first shared state after they diverge, if they meet again
+    pub score_delta: i64,     // second route's score minus the first's
+    pub shared: BTreeSet<Position>,
+    pub only_first: BTreeSet<Position>,
+    pub only_second: BTreeSet<Position>,
+}
+
+impl RouteDiff {
+    // Compares two routes: the common prefix gives the split, the common suffix the rejoin
+    pub fn new(first: &Route, second: &Route) -> Self {
+        let prefix = first
+            .states
+            .iter()
+            .zip(&second.states)
+            .take_while(|(a, b)| a == b)
+            .count();
+        let shortest = first.states.len().min(second.states.len());
+        let suffix = first
+            .states
+            .iter()
+            .rev()
+            .zip(second.states.iter().rev())
+            .take(shortest - prefix)
+            .take_while(|(a, b)| a == b)
+            .count();
+        let identical = first.states == second.states;
+
+        let (first_tiles, second_tiles) = (first.tiles(), second.tiles());
+        RouteDiff {
+            split: (!identical && prefix > 0).then(|| first.states[prefix - 1]),
+            rejoin: (!identical && suffix > 0).then(|| first.states[first.states.len() - suffix]),
+            score_delta: second.score as i64 - first.score as i64,
+            shared: first_tiles.intersection(&second_tiles).copied().collect(),
+            only_first: first_tiles.difference(&second_tiles).copied().collect(),
+            only_second: second_tiles.difference(&first_tiles).copied().collect(),
+        }
+    }
+
+    // One-line description of the divergence
+    pub fn summary(&self) -> String {
+        let describe = |((row, col), facing): State| {
+            format!("row {}, column {} facing {:?}", row, col, facing)
+        };
+        let Some(split) = self.split else {
+            return "the routes are identical".to_string();
+        };
+
+        let mut summary = format!("the routes split at {}", describe(split));
+        match self.rejoin {
+            Some(rejoin) => summary.push_str(&format!(" and rejoin at {}", describe(rejoin))),
+            None => summary.push_str(" and never rejoin"),
+        }
+        summary.push_str(&format!(
+            "; {} tiles only on the first, {} only on the second, score difference {:+}",
+            self.only_first.len(),
+            self.only_second.len(),
+            self.score_delta
+        ));
+        summary
+    }
+}
+
+impl Maze {
+    // Draws two routes: `O` on both, `A` only on the first, `B` only on the second and `*`
+    // where they split and rejoin
+    pub fn render_diff(&self, diff: &RouteDiff) -> String {
+        let marks = [diff.split, diff.rejoin]
+            .into_iter()
+            .flatten()
+            .map(|(position, _)| position)
+            .collect::<BTreeSet<_>>();
+
+        let mut output = String::new();
+        for (row, line) in self.walls.iter().enumerate() {
+            for (col, &wall) in line.iter().enumerate() {
+                let pos = (row, col);
+                output.push(match pos {
+                    _ if wall => '#',
+                    _ if marks.contains(&pos) => '*',
+                    _ if diff.shared.contains(&pos) => 'O',
+                    _ if diff.only_first.contains(&pos) => 'A',
+                    _ if diff.only_second.contains(&pos) => 'B',
+                    _ if pos == self.start => 'S',
+                    _ if pos == self.end => 'E',
+                    _ => '.',
+                });
+            }
+            output.push('\n');
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    const EXAMPLE_1: &str = r#"
+###############
+#.......#....E#
+#.#.###.#.###.#
+#.....#.#...#.#
+#.###.#####.#.#
+#.#.#.......#.#
+#.#.#####.###.#
+#...........#.#
+###.#.#####.#.#
+#...#.....#.#.#
+#.#.#.###.#.#.#
+#.....#...#.#.#
+#.###.#.#.#.#.#
+#S..#.....#...#
+###############
+"#;
+
+    #[test]
+    fn test_best_paths() {
+        let maze = Maze::parse(EXAMPLE_1).unwrap();
+        let best = BestPaths::find(&maze, Costs::default()).unwrap();
+        assert_eq!(best.score(), 7036);
+        assert_eq!(best.tile_count(), 45);
+        assert_eq!(best.end_states(), &[((1, 13), Direction::North)]);
+        assert_eq!(
+            best.predecessors(&maze, ((1, 13), Direction::North)),
+            &[((2, 13), Direction::North)]
+        );
+        assert!(best.tiles().contains(&maze.start()));
+    }
+
+    // Tiles on a best path, from distances to and from every state found by relaxing all
+    // moves until nothing changes. A state is on a best path when the two add up to the score
+    fn brute_force_tiles(maze: &Maze, costs: Costs) -> BTreeSet<Position> {
+        let states: Vec<State> = (0..maze.height())
+            .flat_map(|row| (0..maze.width()).map(move |col| (row, col)))
+            .filter(|&pos| maze.is_open(pos))
+            .flat_map(|pos| Direction::ALL.iter().map(move |&facing| (pos, facing)))
+            .collect();
+        let mut from_start: HashMap<State, usize> = HashMap::from([(maze.start_state(), 0)]);
+        let mut to_end: HashMap<State, usize> = states
+            .iter()
+            .filter(|state| state.0 == maze.end())
+            .map(|&state| (state, 0))
+            .collect();
+
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for &state in &states {
+                for (next, cost) in maze.successors(state, costs) {
+                    if let Some(&distance) = from_start.get(&state)
+                        && from_start
+                            .get(&next)
+                            .is_none_or(|&old| distance + cost < old)
+                    {
+                        from_start.insert(next, distance + cost);
+                        changed = true;
+                    }
+                    if let Some(&distance) = to_end.get(&next)
+                        && to_end.get(&state).is_none_or(|&old| distance + cost < old)
+                    {
+                        to_end.insert(state, distance + cost);
+                        changed = true;
+                    }
+                }
+            }
+        }
+
+        let score = to_end[&maze.start_state()];
+        states
+            .iter()
+            .filter(|state| {
+                from_start
+                    .get(state)
+                    .zip(to_end.get(state))
+                    .is_some_and(|(a, b)| a + b == score)
+            })
+            .map(|state| state.0)
+            .collect()
+    }
+
+    #[test]
+    fn test_custom_costs() {
+        let maze = Maze::parse(EXAMPLE_1).unwrap();
+
+        // With free turns only the path length matters, and turning on the spot must not
+        // hide any of the equally short paths
+        let costs = Costs { step: 1, turn: 0 };
+        let best = BestPaths::find(&maze, costs).unwrap();
+        assert_eq!(best.score(), 28);
+        assert_eq!(best.tile_count(), 37);
+        assert_eq!(best.tiles(), &brute_force_tiles(&maze, costs));
+
+        let costs = Costs { step: 1, turn: 1 };
+        let best = BestPaths::find(&maze, costs).unwrap();
+        assert_eq!(best.tiles(), &brute_force_tiles(&maze, costs));
+        assert_eq!(best.tile_count(), brute_force_tiles(&maze, costs).len());
+
+        // Doubling both costs doubles the score without changing the tiles
+        let costs = Costs {
+            step: 2,
+            turn: 2_000,
+        };
+        let doubled = BestPaths::find(&maze, costs).unwrap();
+        let default = BestPaths::find(&maze, Costs::default()).unwrap();
+        assert_eq!(doubled.score(), 2 * default.score());
+        assert_eq!(doubled.tiles(), default.tiles());
+    }
+
+    #[test]
+    fn test_k_best_routes() {
+        let maze = Maze::parse(EXAMPLE_1).unwrap();
+        let best = BestPaths::find(&maze, Costs::default()).unwrap();
+        let routes = k_best_routes(&maze, Costs::default(), 6);
+
+        assert_eq!(routes.len(), 6);
+        assert!(routes.windows(2).all(|pair| pair[0].score <= pair[1].score));
+        assert_eq!(routes[0].score, 7036);
+        assert_eq!(routes[0].states.first(), Some(&maze.start_state()));
+
+        // The routes with the best score cover exactly the best path tiles
+        let optimal = routes
+            .iter()
+            .take_while(|route| route.score == best.score())
+            .collect::<Vec<_>>();
+        assert_eq!(optimal.len(), 3);
+        let covered = optimal
+            .iter()
+            .flat_map(|route| route.tiles())
+            .collect::<BTreeSet<_>>();
+        assert_eq!(&covered, best.tiles());
+        assert!(routes[3].score > best.score());
+
+        // Every route is distinct and follows legal moves with a matching score
+        let distinct = routes
+            .iter()
+            .map(|route| &route.states)
+            .collect::<HashSet<_>>();
+        assert_eq!(distinct.len(), routes.len());
+        for route in &routes {
+            let score = route
+                .states
+                .windows(2)
+                .map(|pair| {
+                    let next = maze.successors(pair[0], Costs::default());
+                    assert!(next.iter().any(|&(state, _)| state == pair[1]));
+                    move_cost(pair[0], pair[1], Costs::default())
+                })
+                .sum::<usize>();
+            assert_eq!(score, route.score);
+        }
+    }
+
+    #[test]
+    fn test_route_diff() {
+        let maze = Maze::parse(EXAMPLE_1).unwrap();
+        let routes = k_best_routes(&maze, Costs::default(), 2);
+        let diff = RouteDiff::new(&routes[0], &routes[1]);
+
+        assert_eq!(diff.score_delta, 0);
+        assert!(diff.split.is_some() && diff.rejoin.is_some());
+        assert!(!diff.only_first.is_empty() && !diff.only_second.is_empty());
+        assert!(diff.summary().starts_with("the routes split at row "));
+
+        let rendered = maze.render_diff(&diff);
+        assert_eq!(rendered.matches('*').count(), 2);
+        assert_eq!(rendered.matches('A').count(), diff.only_first.len());
+        assert_eq!(rendered.matches('B').count(), diff.only_second.len());
+
+        let same = RouteDiff::new(&routes[0], &routes[0]);
+        assert_eq!(same.summary(), "the routes are identical");
+        assert_eq!(same.shared, routes[0].tiles());
+    }
+
+    #[test]
+    fn test_parse_costs() {
+        let args = |text: &str| {
+            text.split_whitespace()
+                .map(String::from)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(Costs::parse(&[]), Ok(Costs::default()));
+        assert_eq!(
+            Costs::parse(&args("2")),
+            Ok(Costs {
+                step: 2,
+                turn: 1_000
+            })
+        );
+        assert_eq!(Costs::parse(&args("3 7")), Ok(Costs { step: 3, turn: 7 }));
+        assert!(Costs::parse(&args("x")).is_err());
+        assert!(Costs::parse(&args("1 2 3")).is_err());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(Maze::parse("#S.#"), Err(ParseError::MissingEnd));
+        assert_eq!(Maze::parse("#.E#"), Err(ParseError::MissingStart));
+        assert_eq!(
+            Maze::parse("#SxE#"),
+            Err(ParseError::UnexpectedTile('x', (0, 2)))
+        );
+
+        // An enclosed end cannot be reached
+        let maze = Maze::parse("#####\n#S#E#\n#####").unwrap();
+        assert!(BestPaths::find(&maze, Costs::default()).is_none());
+    }
+}
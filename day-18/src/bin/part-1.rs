@@ -1,6 +1,8 @@
-use std::collections::{HashSet, VecDeque};
+use day_18::{
+    Coordinate, MemorySpace, Options, OutputMode, PathFrame, frames_csv, parse_bytes,
+    path_over_time, render_frame,
+};
 
-type Coordinate = (i32, i32);
 type MemoryGrid = Vec<Coordinate>;
 
 /// Reads the input file provided as a command-line argument.
@@ -21,73 +23,53 @@ pub fn read_input_from_path(path: &str) -> String {
     std::fs::read_to_string(path).expect("Failed to read input file")
 }
 
-/// Parses the input data into a vector of grid coordinates (bytes).
-/// Each line represents a coordinate in the form `x,y`.
-fn parse_input(input: &str) -> MemoryGrid {
-    input
-        .lines()
-        .map(|line| {
-            let (x, y) = line.split_once(",").unwrap();
-            (x.trim().parse().unwrap(), y.trim().parse().unwrap())
-        })
-        .collect()
-}
+/// Part 1: Simulates the first 1024 bytes falling into memory and returns the shortest path.
+fn solve(grid: &MemoryGrid) -> u32 {
+    let space = MemorySpace::puzzle(grid);
+    let to_simulate = MemorySpace::puzzle_fallen(grid);
 
-/// Returns the neighboring coordinates for a given grid coordinate.
-fn get_neighbors(coord: &Coordinate) -> Vec<Coordinate> {
-    [(1, 0), (-1, 0), (0, 1), (0, -1)]
-        .iter()
-        .map(|(dx, dy)| (coord.0 + dx, coord.1 + dy))
-        .collect()
+    space.shortest_distance(grid, to_simulate).unwrap() as u32
 }
 
-/// Simulates the grid at a given time step and calculates the shortest path.
-/// Returns the minimum steps to the exit if reachable; otherwise, `None`.
-fn simulate_memory_grid(grid: &MemoryGrid, time: usize) -> Option<u32> {
-    let (width, height) = if grid.len() > 1000 { (71, 71) } else { (7, 7) };
-
-    let mut space: HashSet<Coordinate> = (0..width)
-        .flat_map(|x| (0..height).map(move |y| (x, y)))
-        .collect();
-
-    for coord in grid.iter().take(time) {
-        space.remove(coord);
-    }
-
-    let mut to_explore = VecDeque::from([((0, 0), 0)]);
-    let mut visited = HashSet::new();
-
-    while let Some((current, distance)) = to_explore.pop_back() {
-        if !visited.insert(current) {
-            continue;
-        }
-
-        if current == (width - 1, height - 1) {
-            return Some(distance);
-        }
-
-        for neighbor in get_neighbors(&current) {
-            if !visited.contains(&neighbor) && space.contains(&neighbor) {
-                to_explore.push_front((neighbor, distance + 1));
-            }
+/// Runs the selected output mode for a custom memory space and byte count.
+fn run(options: &Options, grid: &MemoryGrid) -> String {
+    let space = options.space;
+    match options.mode {
+        OutputMode::Result => match space.shortest_distance(grid, options.fallen) {
+            Some(steps) => format!("result: {}\n", steps),
+            None => "the exit is cut off\n".to_string(),
+        },
+        OutputMode::Route => {
+            let frame = PathFrame {
+                fallen: options.fallen,
+                last_byte: options.fallen.checked_sub(1).map(|index| grid[index]),
+                path: space.shortest_path(grid, options.fallen),
+            };
+            render_frame(space, grid, &frame)
         }
+        OutputMode::Frames => path_over_time(space, grid)
+            .iter()
+            .map(|frame| render_frame(space, grid, frame) + "\n")
+            .collect(),
+        OutputMode::Csv => frames_csv(&path_over_time(space, grid)),
     }
-
-    None
-}
-
-/// Part 1: Simulates the first 1024 bytes falling into memory and returns the shortest path.
-fn solve(grid: &MemoryGrid) -> u32 {
-    let to_simulate = if grid.len() > 1000 { 1024 } else { 12 };
-
-    simulate_memory_grid(grid, to_simulate).unwrap()
 }
 
 fn main() {
     let input = read_input_from_arg();
-    let grid = parse_input(&input);
-    let result = solve(&grid);
+    let grid = parse_bytes(&input);
+    let args: Vec<String> = std::env::args().skip(2).collect(); // Options after the input file
+
+    if !args.is_empty() {
+        let options = Options::parse(&args, &grid).unwrap_or_else(|e| {
+            eprintln!("error: {}", e);
+            std::process::exit(1);
+        });
+        print!("{}", run(&options, &grid)); // Print the selected output
+        return;
+    }
 
+    let result = solve(&grid);
     println!("result: {}", result);
 }
 
@@ -123,14 +105,22 @@ mod tests {
 1,6
 2,0"#;
 
-        let grid = parse_input(input.trim());
+        let grid = parse_bytes(input.trim());
         assert_eq!(solve(&grid), 22);
+
+        let args = ["--bytes", "20", "--exit", "6,0"].map(String::from);
+        let options = Options::parse(&args, &grid).unwrap();
+        assert_eq!(run(&options, &grid), "result: 12\n");
+
+        let args = ["--route"].map(String::from);
+        let route = run(&Options::parse(&args, &grid).unwrap(), &grid);
+        assert!(route.starts_with("After 12 bytes (last at 5,1): 22 steps\nOO.#OOO\n"));
     }
 
     #[test]
     fn test_with_input() {
         let input = read_input_from_path("docs/challenge_1.txt");
-        let grid = parse_input(&input);
+        let grid = parse_bytes(&input);
         let result = solve(&grid);
 
         assert_eq!(result, 374)
@@ -1,6 +1,8 @@
-use std::collections::{HashSet, VecDeque};
+use day_18::{
+    Connectivity, Coordinate, MemorySpace, Options, OutputMode, first_blocking_byte, frames_csv,
+    parse_bytes, path_over_time, render_frame,
+};
 
-type Coordinate = (i32, i32);
 type MemoryGrid = Vec<Coordinate>;
 
 /// Reads the input file provided as a command-line argument.
@@ -21,83 +23,61 @@ pub fn read_input_from_path(path: &str) -> String {
     std::fs::read_to_string(path).expect("Failed to read input file")
 }
 
-/// Parses the input data into a vector of grid coordinates (bytes).
-/// Each line represents a coordinate in the form `x,y`.
-fn parse_input(input: &str) -> MemoryGrid {
-    input
-        .lines()
-        .map(|line| {
-            let (x, y) = line.split_once(",").unwrap();
-            (x.trim().parse().unwrap(), y.trim().parse().unwrap())
-        })
-        .collect()
-}
+/// Part 2: Finds the first byte that blocks the path to the exit.
+fn solve(grid: &MemoryGrid) -> String {
+    let blocking =
+        first_blocking_byte(MemorySpace::puzzle(grid), grid).expect("the exit is never cut off");
 
-/// Returns the neighboring coordinates for a given grid coordinate.
-fn get_neighbors(coord: &Coordinate) -> Vec<Coordinate> {
-    [(1, 0), (-1, 0), (0, 1), (0, -1)]
-        .iter()
-        .map(|(dx, dy)| (coord.0 + dx, coord.1 + dy))
-        .collect()
+    format!("{},{}", grid[blocking].0, grid[blocking].1)
 }
 
-/// Simulates the grid at a given time step and calculates the shortest path.
-/// Returns the minimum steps to the exit if reachable; otherwise, `None`.
-fn simulate_memory_grid(grid: &MemoryGrid, time: usize) -> Option<u32> {
-    let (width, height) = if grid.len() > 1000 { (71, 71) } else { (7, 7) };
-
-    let mut space: HashSet<Coordinate> = (0..width)
-        .flat_map(|x| (0..height).map(move |y| (x, y)))
-        .collect();
-
-    for coord in grid.iter().take(time) {
-        space.remove(coord);
-    }
-
-    let mut to_explore = VecDeque::from([((0, 0), 0)]);
-    let mut visited = HashSet::new();
-
-    while let Some((current, distance)) = to_explore.pop_back() {
-        if !visited.insert(current) {
-            continue;
-        }
-
-        if current == (width - 1, height - 1) {
-            return Some(distance);
-        }
+/// Lists the last route to the exit before it is cut off. Each coordinate is paired with
+/// the time step at which it was added to the region reachable from the start, i.e. the
+/// number of fallen bytes it stays reachable until.
+fn describe_last_route(space: MemorySpace, grid: &MemoryGrid) -> String {
+    let connectivity = Connectivity::new(space, grid);
+    let Some(route) = connectivity.last_route() else {
+        return String::from("the exit is never reachable\n");
+    };
 
-        for neighbor in get_neighbors(&current) {
-            if !visited.contains(&neighbor) && space.contains(&neighbor) {
-                to_explore.push_front((neighbor, distance + 1));
-            }
-        }
-    }
-
-    None
+    route
+        .iter()
+        .map(|((x, y), fallen)| format!("{},{}: {}\n", x, y, fallen))
+        .collect()
 }
 
-/// Part 2: Finds the first byte that blocks the path to the exit.
-fn solve(grid: &MemoryGrid) -> String {
-    let mut reachable = 0;
-    let mut unreachable = grid.len() - 1;
-
-    while unreachable > (reachable + 1) {
-        let midpoint = (reachable + unreachable) / 2;
-        if simulate_memory_grid(grid, midpoint).is_some() {
-            reachable = midpoint;
-        } else {
-            unreachable = midpoint;
-        }
+/// Runs the selected output mode for a custom memory space.
+fn run(options: &Options, grid: &MemoryGrid) -> String {
+    let space = options.space;
+    match options.mode {
+        OutputMode::Result => match first_blocking_byte(space, grid) {
+            Some(blocking) => format!("result: {},{}\n", grid[blocking].0, grid[blocking].1),
+            None => "the exit is never cut off\n".to_string(),
+        },
+        OutputMode::Route => describe_last_route(space, grid),
+        OutputMode::Frames => path_over_time(space, grid)
+            .iter()
+            .map(|frame| render_frame(space, grid, frame) + "\n")
+            .collect(),
+        OutputMode::Csv => frames_csv(&path_over_time(space, grid)),
     }
-
-    format!("{},{}", grid[reachable].0, grid[reachable].1)
 }
 
 fn main() {
     let input = read_input_from_arg();
-    let grid = parse_input(&input);
-    let result = solve(&grid);
+    let grid = parse_bytes(&input);
+    let args: Vec<String> = std::env::args().skip(2).collect(); // Options after the input file
+
+    if !args.is_empty() {
+        let options = Options::parse(&args, &grid).unwrap_or_else(|e| {
+            eprintln!("error: {}", e);
+            std::process::exit(1);
+        });
+        print!("{}", run(&options, &grid)); // Print the selected output
+        return;
+    }
 
+    let result = solve(&grid);
     println!("result: {}", result);
 }
 
@@ -133,15 +113,23 @@ mod tests {
         1,6
         2,0"#;
 
-        let grid = parse_input(input.trim());
+        let grid = parse_bytes(input.trim());
         // The byte at 6,1 is the first that prevents the exit from being reachable.
         assert_eq!(solve(&grid), "6,1");
+
+        let route = describe_last_route(MemorySpace::puzzle(&grid), &grid);
+        assert!(route.starts_with("0,0: 25\n"));
+        assert!(route.ends_with("6,6: 20\n"));
+
+        let args = ["--size", "9,7"].map(String::from);
+        let options = Options::parse(&args, &grid).unwrap();
+        assert_eq!(run(&options, &grid), "the exit is never cut off\n");
     }
 
     #[test]
     fn test_with_input() {
         let input = read_input_from_path("docs/challenge_2.txt");
-        let grid = parse_input(&input);
+        let grid = parse_bytes(&input);
         let result = solve(&grid);
 
         assert_eq!(result, "30,12")
@@ -0,0 +1,619 @@
+use std::collections::VecDeque;
+
+pub type Coordinate = (i32, i32);
+
+/// Parses the falling bytes, one `x,y` coordinate per line, skipping blank lines.
+pub fn parse_bytes(input: &str) -> Vec<Coordinate> {
+    input
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let (x, y) = line.split_once(",").unwrap();
+            (x.trim().parse().unwrap(), y.trim().parse().unwrap())
+        })
+        .collect()
+}
+
+/// The memory space bytes fall into. By default the path runs from the top-left corner
+/// to the bottom-right corner.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MemorySpace {
+    pub width: i32,
+    pub height: i32,
+    start: Coordinate,
+    exit: Coordinate,
+}
+
+impl MemorySpace {
+    /// Creates a memory space of the given size with the start and exit in opposite corners.
+    pub fn new(width: i32, height: i32) -> Self {
+        MemorySpace {
+            width,
+            height,
+            start: (0, 0),
+            exit: (width - 1, height - 1),
+        }
+    }
+
+    /// Picks the puzzle's memory space: 71x71 for the real input, 7x7 for the example.
+    pub fn puzzle(bytes: &[Coordinate]) -> Self {
+        if bytes.len() > 1000 {
+            MemorySpace::new(71, 71)
+        } else {
+            MemorySpace::new(7, 7)
+        }
+    }
+
+    /// Picks how many bytes have fallen in part 1: 1024 for the real input, 12 for the example.
+    pub fn puzzle_fallen(bytes: &[Coordinate]) -> usize {
+        if bytes.len() > 1000 { 1024 } else { 12 }
+    }
+
+    /// Moves the start to another coordinate.
+    pub fn with_start(mut self, start: Coordinate) -> Self {
+        self.start = start;
+        self
+    }
+
+    /// Moves the exit to another coordinate.
+    pub fn with_exit(mut self, exit: Coordinate) -> Self {
+        self.exit = exit;
+        self
+    }
+
+    /// Returns the starting coordinate.
+    pub fn start(&self) -> Coordinate {
+        self.start
+    }
+
+    /// Returns the exit coordinate.
+    pub fn exit(&self) -> Coordinate {
+        self.exit
+    }
+
+    /// Returns the number of cells.
+    pub fn len(&self) -> usize {
+        (self.width.max(0) * self.height.max(0)) as usize
+    }
+
+    /// Checks whether the memory space has no cells.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Checks whether a coordinate lies inside the memory space.
+    pub fn contains(&self, (x, y): Coordinate) -> bool {
+        (0..self.width).contains(&x) && (0..self.height).contains(&y)
+    }
+
+    fn index(&self, (x, y): Coordinate) -> usize {
+        (y * self.width + x) as usize
+    }
+
+    fn coordinate(&self, index: usize) -> Coordinate {
+        (index as i32 % self.width, index as i32 / self.width)
+    }
+
+    /// Returns the in-bounds neighbours of a cell index.
+    fn neighbors(&self, index: usize) -> impl Iterator<Item = usize> + '_ {
+        let (x, y) = self.coordinate(index);
+        [(1, 0), (-1, 0), (0, 1), (0, -1)]
+            .into_iter()
+            .map(move |(dx, dy)| (x + dx, y + dy))
+            .filter(|&coord| self.contains(coord))
+            .map(|coord| self.index(coord))
+    }
+
+    /// Returns, for every cell, the index of the first byte that corrupts it, or
+    /// `bytes.len()` if no byte ever lands there. Bytes outside the space are ignored.
+    pub fn drop_times(&self, bytes: &[Coordinate]) -> Vec<usize> {
+        let mut times = vec![bytes.len(); self.len()];
+        for (time, &coord) in bytes.iter().enumerate().rev() {
+            if self.contains(coord) {
+                times[self.index(coord)] = time;
+            }
+        }
+        times
+    }
+
+    /// Finds a shortest path from the start to the exit once `fallen` bytes have landed,
+    /// including both ends, or `None` if the exit cannot be reached.
+    pub fn shortest_path(&self, bytes: &[Coordinate], fallen: usize) -> Option<Vec<Coordinate>> {
+        let times = self.drop_times(bytes);
+        self.shortest_path_open(|index| times[index] >= fallen)
+    }
+
+    /// Finds the number of steps from the start to the exit once `fallen` bytes have landed.
+    pub fn shortest_distance(&self, bytes: &[Coordinate], fallen: usize) -> Option<usize> {
+        self.shortest_path(bytes, fallen).map(|path| path.len() - 1)
+    }
+
+    /// Breadth-first search through the cells for which `open` holds.
+    fn shortest_path_open(&self, open: impl Fn(usize) -> bool) -> Option<Vec<Coordinate>> {
+        let (start, exit) = (self.start(), self.exit());
+        if !self.contains(start) || !self.contains(exit) || !open(self.index(start)) {
+            return None;
+        }
+
+        let mut previous = vec![None; self.len()];
+        let mut to_explore = VecDeque::from([self.index(start)]);
+        previous[self.index(start)] = Some(self.index(start));
+
+        while let Some(current) = to_explore.pop_front() {
+            if current == self.index(exit) {
+                let mut path = vec![self.coordinate(current)];
+                let mut cell = current;
+                while cell != self.index(start) {
+                    cell = previous[cell].unwrap();
+                    path.push(self.coordinate(cell));
+                }
+                path.reverse();
+                return Some(path);
+            }
+
+            for neighbor in self.neighbors(current) {
+                if previous[neighbor].is_none() && open(neighbor) {
+                    previous[neighbor] = Some(current);
+                    to_explore.push_back(neighbor);
+                }
+            }
+        }
+
+        None
+    }
+}
+
+/// Union-find over cell indices that also keeps each component's members, so the cells
+/// joining the start's component can be stamped with the time they joined.
+struct DisjointSet {
+    parent: Vec<usize>,
+    members: Vec<Vec<usize>>,
+}
+
+impl DisjointSet {
+    fn new(size: usize) -> Self {
+        DisjointSet {
+            parent: (0..size).collect(),
+            members: (0..size).map(|index| vec![index]).collect(),
+        }
+    }
+
+    fn find(&mut self, mut index: usize) -> usize {
+        while self.parent[index] != index {
+            self.parent[index] = self.parent[self.parent[index]]; // Path halving
+            index = self.parent[index];
+        }
+        index
+    }
+
+    /// Merges the smaller component into the larger one.
+    fn union(&mut self, a: usize, b: usize) {
+        let (mut root, mut absorbed) = (self.find(a), self.find(b));
+        if root == absorbed {
+            return;
+        }
+        if self.members[root].len() < self.members[absorbed].len() {
+            std::mem::swap(&mut root, &mut absorbed);
+        }
+
+        self.parent[absorbed] = root;
+        let moved = std::mem::take(&mut self.members[absorbed]);
+        self.members[root].extend(moved);
+    }
+}
+
+/// How long every cell stays connected to the start as bytes keep falling.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Connectivity {
+    space: MemorySpace,
+    byte_count: usize,
+    reachable_until: Vec<Option<usize>>,
+}
+
+impl Connectivity {
+    /// Computes connectivity by removing the bytes in reverse order. Every freed cell is
+    /// merged with its free neighbours, and whenever a component joins the start's, its
+    /// cells are stamped with the current time step. This takes near-linear time overall.
+    pub fn new(space: MemorySpace, bytes: &[Coordinate]) -> Self {
+        let times = space.drop_times(bytes);
+        let mut sets = DisjointSet::new(space.len());
+        let mut free = vec![false; space.len()];
+        let mut reachable_until = vec![None; space.len()];
+        let start = space
+            .contains(space.start())
+            .then(|| space.index(space.start()));
+
+        // Frees a cell at time step `fallen`, stamping any cells newly joined to the start
+        let mut open_cell = |cell: usize, fallen: usize| {
+            free[cell] = true;
+            if Some(cell) == start {
+                reachable_until[cell] = Some(fallen);
+            }
+            for neighbor in space.neighbors(cell).collect::<Vec<_>>() {
+                if !free[neighbor] {
+                    continue;
+                }
+                let (cell_root, neighbor_root) = (sets.find(cell), sets.find(neighbor));
+                let (cell_reached, neighbor_reached) = (
+                    reachable_until[cell_root].is_some(),
+                    reachable_until[neighbor_root].is_some(),
+                );
+                let newcomers = match (cell_reached, neighbor_reached) {
+                    (true, false) => sets.members[neighbor_root].clone(),
+                    (false, true) => sets.members[cell_root].clone(),
+                    _ => Vec::new(),
+                };
+                for member in newcomers {
+                    reachable_until[member] = Some(fallen);
+                }
+                sets.union(cell, neighbor);
+            }
+        };
+
+        let mut order = (0..space.len()).collect::<Vec<_>>();
+        order.sort_by_key(|&cell| std::cmp::Reverse(times[cell]));
+        for cell in order {
+            open_cell(cell, times[cell]);
+        }
+
+        Connectivity {
+            space,
+            byte_count: bytes.len(),
+            reachable_until,
+        }
+    }
+
+    /// Returns the largest number of fallen bytes for which `coord` can still be reached
+    /// from the start, i.e. the time step at which the reverse sweep added it to the start's
+    /// region, or `None` if it is never reachable.
+    pub fn reachable_until(&self, coord: Coordinate) -> Option<usize> {
+        if !self.space.contains(coord) {
+            return None;
+        }
+        self.reachable_until[self.space.index(coord)]
+    }
+
+    /// Returns the index of the first byte that cuts the exit off from the start, or `None`
+    /// if no byte does (the exit stays reachable after every byte has fallen).
+    pub fn blocking_byte(&self) -> Option<usize> {
+        self.reachable_until(self.space.exit())
+            .filter(|&fallen| fallen < self.byte_count)
+    }
+
+    /// Returns the shortest path just before the exit is cut off, pairing each coordinate
+    /// with the time step at which it was added to the start's region.
+    pub fn last_route(&self) -> Option<Vec<(Coordinate, usize)>> {
+        let open_until = self.reachable_until(self.space.exit())?;
+        let path = self.space.shortest_path_open(|index| {
+            self.reachable_until[index].is_some_and(|fallen| fallen >= open_until)
+        })?;
+
+        Some(
+            path.into_iter()
+                .map(|coord| (coord, self.reachable_until(coord).unwrap()))
+                .collect(),
+        )
+    }
+}
+
+/// Shortest path after a given number of bytes have fallen, one frame of the animation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PathFrame {
+    /// Number of bytes fallen so far.
+    pub fallen: usize,
+    /// Coordinates of the byte that fell last, if any.
+    pub last_byte: Option<Coordinate>,
+    /// Shortest path including both ends, or `None` once the exit is cut off.
+    pub path: Option<Vec<Coordinate>>,
+}
+
+/// Computes the shortest path after every byte drop, from no bytes up to all of them.
+/// The search only reruns when a byte lands on the current path, because removing a
+/// cell elsewhere cannot make any other route shorter.
+pub fn path_over_time(space: MemorySpace, bytes: &[Coordinate]) -> Vec<PathFrame> {
+    let mut frames = Vec::with_capacity(bytes.len() + 1);
+    let mut path = space.shortest_path(bytes, 0);
+    frames.push(PathFrame {
+        fallen: 0,
+        last_byte: None,
+        path: path.clone(),
+    });
+
+    for (index, &byte) in bytes.iter().enumerate() {
+        if path.as_ref().is_some_and(|cells| cells.contains(&byte)) {
+            path = space.shortest_path(bytes, index + 1);
+        }
+        frames.push(PathFrame {
+            fallen: index + 1,
+            last_byte: Some(byte),
+            path: path.clone(),
+        });
+    }
+
+    frames
+}
+
+/// Exports the frames as CSV for plotting, one row per path coordinate. Frames where
+/// the exit is cut off have no rows.
+pub fn frames_csv(frames: &[PathFrame]) -> String {
+    let mut csv = String::from("fallen,step,x,y\n");
+    for frame in frames {
+        for (step, (x, y)) in frame.path.iter().flatten().enumerate() {
+            csv.push_str(&format!("{},{},{},{}\n", frame.fallen, step, x, y));
+        }
+    }
+    csv
+}
+
+/// Draws a frame as ASCII art: `#` for corrupted memory, `O` for the path and `.` for
+/// safe cells, below a header describing the frame.
+pub fn render_frame(space: MemorySpace, bytes: &[Coordinate], frame: &PathFrame) -> String {
+    let times = space.drop_times(bytes);
+    let mut cells = times
+        .iter()
+        .map(|&time| if time < frame.fallen { '#' } else { '.' })
+        .collect::<Vec<_>>();
+    for &coord in frame.path.iter().flatten() {
+        cells[space.index(coord)] = 'O';
+    }
+
+    let mut output = format!("After {} bytes", frame.fallen);
+    if let Some((x, y)) = frame.last_byte {
+        output.push_str(&format!(" (last at {},{})", x, y));
+    }
+    match &frame.path {
+        Some(path) => output.push_str(&format!(": {} steps\n", path.len() - 1)),
+        None => output.push_str(": exit cut off\n"),
+    }
+    for row in cells.chunks(space.width.max(1) as usize) {
+        output.extend(row);
+        output.push('\n');
+    }
+    output
+}
+
+/// Output selected on the command line after the input file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputMode {
+    Result, // the part's puzzle answer
+    Route,  // the route the part is about, with its coordinates
+    Frames, // ASCII frame of the shortest path after every byte drop
+    Csv,    // shortest path after every byte drop as CSV
+}
+
+/// Options read from the command line:
+/// `[--size W,H] [--bytes N] [--start X,Y] [--exit X,Y] [--route | --frames | --csv]`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Options {
+    pub space: MemorySpace,
+    pub fallen: usize,
+    pub mode: OutputMode,
+}
+
+impl Options {
+    /// Parses the arguments that follow the input file. The memory space and byte count
+    /// default to the puzzle's, chosen from the number of bytes.
+    pub fn parse(args: &[String], bytes: &[Coordinate]) -> Result<Options, String> {
+        let mut size = None;
+        let mut start = None;
+        let mut exit = None;
+        let mut fallen = MemorySpace::puzzle_fallen(bytes);
+        let mut mode = OutputMode::Result;
+        let mut args = args.iter();
+
+        while let Some(arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .ok_or_else(|| format!("Missing value for {}", arg))
+            };
+            match arg.as_str() {
+                "--size" => size = Some(parse_pair(value()?)?),
+                "--start" => start = Some(parse_pair(value()?)?),
+                "--exit" => exit = Some(parse_pair(value()?)?),
+                "--bytes" => {
+                    let count = value()?;
+                    fallen = count
+                        .parse()
+                        .map_err(|_| format!("Invalid byte count: {}", count))?;
+                }
+                "--route" => mode = OutputMode::Route,
+                "--frames" => mode = OutputMode::Frames,
+                "--csv" => mode = OutputMode::Csv,
+                _ => return Err(format!("Invalid argument: {}", arg)),
+            }
+        }
+
+        let mut space = match size {
+            Some((width, height)) if width > 0 && height > 0 => MemorySpace::new(width, height),
+            Some(_) => return Err("The memory space must not be empty".to_string()),
+            None => MemorySpace::puzzle(bytes),
+        };
+        if let Some(start) = start {
+            space = space.with_start(start);
+        }
+        if let Some(exit) = exit {
+            space = space.with_exit(exit);
+        }
+        for (name, coord) in [("start", space.start()), ("exit", space.exit())] {
+            if !space.contains(coord) {
+                return Err(format!(
+                    "The {} {:?} is outside the memory space",
+                    name, coord
+                ));
+            }
+        }
+
+        Ok(Options {
+            space,
+            fallen: fallen.min(bytes.len()),
+            mode,
+        })
+    }
+}
+
+/// Parses a pair of integers written `a,b` (or `axb` for sizes).
+fn parse_pair(text: &str) -> Result<(i32, i32), String> {
+    text.split_once([',', 'x'])
+        .and_then(|(a, b)| Some((a.trim().parse().ok()?, b.trim().parse().ok()?)))
+        .ok_or_else(|| format!("Invalid pair: {}", text))
+}
+
+/// Finds the index of the first byte that cuts the exit off from the start.
+pub fn first_blocking_byte(space: MemorySpace, bytes: &[Coordinate]) -> Option<usize> {
+    Connectivity::new(space, bytes).blocking_byte()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "5,4\n4,2\n4,5\n3,0\n2,1\n6,3\n2,4\n1,5\n0,6\n3,3\n2,6\n5,1\n1,2\n5,5\n2,5\n6,5\n1,4\n0,4\n6,4\n1,1\n6,1\n1,0\n0,5\n1,6\n2,0";
+
+    /// Checks the reverse sweep against one breadth-first search per time step.
+    fn assert_matches_brute_force(space: MemorySpace, bytes: &[Coordinate]) {
+        let connectivity = Connectivity::new(space, bytes);
+        let times = space.drop_times(bytes);
+        let mut expected = vec![None; space.len()];
+
+        for fallen in 0..=bytes.len() {
+            let mut seen = vec![false; space.len()];
+            let start = space.index(space.start());
+            let mut queue = VecDeque::new();
+            if times[start] >= fallen {
+                seen[start] = true;
+                queue.push_back(start);
+            }
+            while let Some(cell) = queue.pop_front() {
+                expected[cell] = Some(fallen);
+                for neighbor in space.neighbors(cell) {
+                    if !seen[neighbor] && times[neighbor] >= fallen {
+                        seen[neighbor] = true;
+                        queue.push_back(neighbor);
+                    }
+                }
+            }
+        }
+
+        for (index, &fallen) in expected.iter().enumerate() {
+            let coord = space.coordinate(index);
+            assert_eq!(connectivity.reachable_until(coord), fallen, "{:?}", coord);
+        }
+    }
+
+    #[test]
+    fn test_blocking_byte() {
+        let bytes = parse_bytes(EXAMPLE);
+        let space = MemorySpace::puzzle(&bytes);
+        assert_eq!(first_blocking_byte(space, &bytes), Some(20));
+        assert_eq!(bytes[20], (6, 1));
+        assert_matches_brute_force(space, &bytes);
+
+        // Bytes that never block the exit
+        assert_eq!(first_blocking_byte(space, &bytes[..12]), None);
+    }
+
+    #[test]
+    fn test_blocking_byte_with_input() {
+        let bytes = parse_bytes(&std::fs::read_to_string("docs/challenge_2.txt").unwrap());
+        let space = MemorySpace::puzzle(&bytes);
+        let blocking = first_blocking_byte(space, &bytes).unwrap();
+        assert_eq!(bytes[blocking], (30, 12));
+        assert!(space.shortest_path(&bytes, blocking).is_some());
+        assert!(space.shortest_path(&bytes, blocking + 1).is_none());
+    }
+
+    #[test]
+    fn test_last_route() {
+        let bytes = parse_bytes(EXAMPLE);
+        let connectivity = Connectivity::new(MemorySpace::puzzle(&bytes), &bytes);
+        let route = connectivity.last_route().unwrap();
+
+        assert_eq!(route.first(), Some(&((0, 0), 25)));
+        assert_eq!(route.last(), Some(&((6, 6), 20)));
+        assert!(route.iter().all(|&(_, fallen)| fallen >= 20));
+        assert!(route.windows(2).all(|pair| {
+            let ((x1, y1), (x2, y2)) = (pair[0].0, pair[1].0);
+            (x1 - x2).abs() + (y1 - y2).abs() == 1
+        }));
+    }
+
+    #[test]
+    fn test_custom_space() {
+        let bytes = parse_bytes(EXAMPLE);
+        let space = MemorySpace::new(7, 7).with_start((6, 0)).with_exit((0, 6));
+        assert_eq!(space.shortest_distance(&bytes, 0), Some(12));
+        assert_matches_brute_force(space, &bytes);
+
+        let blocking = first_blocking_byte(space, &bytes).unwrap();
+        assert!(space.shortest_path(&bytes, blocking).is_some());
+        assert!(space.shortest_path(&bytes, blocking + 1).is_none());
+
+        // A wider space leaves room to go around every byte
+        assert_eq!(first_blocking_byte(MemorySpace::new(9, 7), &bytes), None);
+    }
+
+    #[test]
+    fn test_path_over_time() {
+        let bytes = parse_bytes(EXAMPLE);
+        let space = MemorySpace::puzzle(&bytes);
+        let frames = path_over_time(space, &bytes);
+
+        assert_eq!(frames.len(), bytes.len() + 1);
+        for frame in &frames {
+            assert_eq!(
+                frame.path.as_ref().map(|path| path.len() - 1),
+                space.shortest_distance(&bytes, frame.fallen)
+            );
+        }
+
+        let rendered = render_frame(space, &bytes, &frames[12]);
+        assert_eq!(
+            rendered.lines().next(),
+            Some("After 12 bytes (last at 5,1): 22 steps")
+        );
+        assert_eq!(rendered.lines().count(), 8);
+        assert_eq!(rendered.matches('O').count(), 23);
+        assert_eq!(rendered.matches('#').count(), 12);
+
+        let csv = frames_csv(&frames);
+        assert_eq!(csv.lines().next(), Some("fallen,step,x,y"));
+        assert!(csv.lines().any(|line| line == "20,0,0,0"));
+        assert!(!csv.lines().any(|line| line.starts_with("21,")));
+    }
+
+    #[test]
+    fn test_options() {
+        let bytes = parse_bytes(EXAMPLE);
+        let args = |text: &str| {
+            text.split_whitespace()
+                .map(String::from)
+                .collect::<Vec<_>>()
+        };
+
+        let options = Options::parse(&[], &bytes).unwrap();
+        assert_eq!(options.space, MemorySpace::new(7, 7));
+        assert_eq!(options.fallen, 12);
+        assert_eq!(options.mode, OutputMode::Result);
+
+        let options =
+            Options::parse(&args("--size 9x8 --bytes 5 --start 1,1 --frames"), &bytes).unwrap();
+        assert_eq!(options.space, MemorySpace::new(9, 8).with_start((1, 1)));
+        assert_eq!(options.space.exit(), (8, 7));
+        assert_eq!(options.fallen, 5);
+        assert_eq!(options.mode, OutputMode::Frames);
+
+        assert!(Options::parse(&args("--exit 7,7"), &bytes).is_err());
+        assert!(Options::parse(&args("--bytes"), &bytes).is_err());
+        assert!(Options::parse(&args("--plot"), &bytes).is_err());
+    }
+
+    #[test]
+    fn test_shortest_distance() {
+        let bytes = parse_bytes(EXAMPLE);
+        let space = MemorySpace::puzzle(&bytes);
+        assert_eq!(space.shortest_distance(&bytes, 12), Some(22));
+        assert_eq!(space.shortest_distance(&bytes, 0), Some(12));
+        assert_eq!(space.shortest_distance(&bytes, 21), None);
+    }
+}
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use day_19::{format_arrangement, parse_input};
 
 /// Reads the input file provided as a command-line argument.
 /// Returns the content of the file as a `String`.
@@ -18,56 +18,37 @@ pub fn read_file_from_path(file_path: &str) -> String {
     std::fs::read_to_string(file_path).expect("Failed to read input file")
 }
 
-/// Recursive function to calculate the number of ways to assemble a design.
-/// `design_bytes` represents the target design as a byte slice.
-/// `available_towel_patterns` is a list of towel patterns as byte slices.
-/// `memoization_cache` stores previously computed results to optimize performance.
-pub fn count_ways_to_assemble<'a>(
-    design_bytes: &'a [u8],
-    patterns: &[&[u8]],
-    cache: &mut HashMap<&'a [u8], usize>,
-) -> usize {
-    if design_bytes.is_empty() {
-        return 1;
-    }
-
-    if let Some(&cached_count) = cache.get(&design_bytes) {
-        return cached_count;
-    }
+/// count how many designs can be assembled.
+pub fn solve(input: &str) -> usize {
+    let (matcher, designs) = parse_input(input);
 
-    let result = patterns
+    designs
         .iter()
-        .filter(|&pattern| design_bytes.starts_with(pattern))
-        .map(|&pattern| count_ways_to_assemble(&design_bytes[pattern.len()..], patterns, cache))
-        .sum();
-
-    cache.insert(design_bytes, result);
-    result
+        .filter(|design| matcher.is_possible(design))
+        .count()
 }
 
-/// count how many designs can be assembled.
-pub fn solve(input: &str) -> usize {
-    let (towel_patterns, designs) = input.split_once("\n\n").unwrap_or(("", ""));
-    let available_towel_patterns = towel_patterns
-        .split(", ")
-        .map(str::as_bytes)
-        .collect::<Vec<_>>();
-    let mut cache = HashMap::new();
-    let mut result = 0; // possible designs count
-
-    for design in designs.lines() {
-        let ways = count_ways_to_assemble(design.as_bytes(), &available_towel_patterns, &mut cache);
-        if ways > 0 {
-            result += 1;
-        }
-    }
+/// Lists one arrangement for every possible design, or marks it as impossible.
+pub fn describe_witnesses(input: &str) -> String {
+    let (matcher, designs) = parse_input(input);
 
-    result
+    designs
+        .iter()
+        .map(|design| match matcher.witness(design) {
+            Some(pieces) => format!("{}: {}\n", design, format_arrangement(&pieces)),
+            None => format!("{}: impossible\n", design),
+        })
+        .collect()
 }
 
 fn main() {
     let input = read_file_from_args();
 
+    if std::env::args().nth(2).as_deref() == Some("--witness") {
+        print!("{}", describe_witnesses(&input)); // One arrangement per design
+        return;
+    }
+
     println!("result: {:?}", solve(&input));
 }
 
@@ -103,6 +84,10 @@ brgr
 bbrgwb"#;
 
         assert_eq!(solve(EXAMPLE), 6);
+
+        let witnesses = describe_witnesses(EXAMPLE);
+        assert!(witnesses.contains("bggr: b|g|g|r\n"));
+        assert!(witnesses.contains("bbrgwb: impossible\n"));
     }
 
     #[test]
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use day_19::{BigCount, format_arrangement, parse_input};
 
 /// Reads the input file provided as a command-line argument.
 /// Returns the content of the file as a `String`.
@@ -18,57 +18,48 @@ pub fn read_file_from_path(file_path: &str) -> String {
     std::fs::read_to_string(file_path).expect("Failed to read input file")
 }
 
-/// Recursive function to calculate the number of ways to assemble a design.
-/// `design_bytes` represents the target design as a byte slice.
-/// `available_towel_patterns` is a list of towel patterns as byte slices.
-/// `memoization_cache` stores previously computed results to optimize performance.
-pub fn count_ways_to_assemble<'a>(
-    design_bytes: &'a [u8],
-    patterns: &[&[u8]],
-    cache: &mut HashMap<&'a [u8], usize>,
-) -> usize {
-    if design_bytes.is_empty() {
-        return 1;
-    }
-
-    if let Some(&cached_count) = cache.get(&design_bytes) {
-        return cached_count;
-    }
+/// count the total number of ways to assemble all designs.
+pub fn solve(input: &str) -> BigCount {
+    let (matcher, designs) = parse_input(input);
 
-    let result = patterns
+    designs
         .iter()
-        .filter(|&pattern| design_bytes.starts_with(pattern))
-        .map(|&pattern| count_ways_to_assemble(&design_bytes[pattern.len()..], patterns, cache))
-        .sum();
-
-    cache.insert(design_bytes, result);
-    result
+        .map(|design| matcher.count_arrangements(design))
+        .sum()
 }
 
-/// count the total number of ways to assemble all designs.
-pub fn solve(input: &str) -> usize {
-    let (towel_patterns, designs) = input.split_once("\n\n").unwrap_or(("", ""));
-    let available_towel_patterns = towel_patterns
-        .split(", ")
-        .map(str::as_bytes)
-        .collect::<Vec<_>>();
-    let mut cache = HashMap::new();
-    let mut result = 0;
-
-    for design in designs.lines() {
-        let ways = count_ways_to_assemble(design.as_bytes(), &available_towel_patterns, &mut cache);
-        if ways > 0 {
-            result += ways;
+/// Lists the arrangement count of every design followed by up to `limit` arrangements.
+pub fn describe_arrangements(input: &str, limit: usize) -> String {
+    let (matcher, designs) = parse_input(input);
+    let mut report = String::new();
+
+    for design in designs {
+        let count = matcher.count_arrangements(design);
+        report.push_str(&format!("{}: {} arrangements\n", design, count));
+        for pieces in matcher.arrangements(design).take(limit) {
+            report.push_str(&format!("  {}\n", format_arrangement(&pieces)));
         }
     }
 
-    result
+    report
 }
 
 fn main() {
     let input = read_file_from_args();
+    let args: Vec<String> = std::env::args().skip(2).collect(); // Options after the input file
+
+    if args.first().map(String::as_str) == Some("--arrangements") {
+        let limit = args.get(1).map_or(10, |limit| {
+            limit.parse().unwrap_or_else(|_| {
+                eprintln!("error: invalid arrangement limit '{}'", limit);
+                std::process::exit(1);
+            })
+        });
+        print!("{}", describe_arrangements(&input, limit)); // List arrangements per design
+        return;
+    }
 
-    println!("result: {:?}", solve(&input));
+    println!("result: {}", solve(&input));
 }
 
 #[cfg(test)]
@@ -78,7 +69,7 @@ mod tests {
     #[test]
     fn test_with_empty_input() {
         const EMPTY: &str = "";
-        assert_eq!(solve(EMPTY), 0);
+        assert_eq!(solve(EMPTY), BigCount::zero());
     }
 
     #[test]
@@ -86,7 +77,7 @@ mod tests {
         const INPUT: &str = r"b
 
 b";
-        assert_eq!(solve(INPUT), 1);
+        assert_eq!(solve(INPUT), BigCount::one());
     }
 
     #[test]
@@ -102,14 +93,18 @@ bwurrg
 brgr
 bbrgwb"#;
 
-        assert_eq!(solve(EXAMPLE), 16);
+        assert_eq!(solve(EXAMPLE), BigCount::from(16));
+
+        let report = describe_arrangements(EXAMPLE, 1);
+        assert!(report.starts_with("brwrr: 2 arrangements\n  b|r|wr|r\n"));
+        assert!(report.contains("ubwu: 0 arrangements\n"));
     }
 
     #[test]
     fn test_with_input_2() {
         assert_eq!(
             solve(&read_file_from_path("docs/challenge_2.txt")),
-            723524534506343
+            BigCount::from(723524534506343)
         );
     }
 }
@@ -0,0 +1,473 @@
+use std::fmt;
+use std::ops::{Add, AddAssign};
+
+/// Each limb of a [`BigCount`] holds 18 decimal digits.
+const LIMB_BASE: u64 = 1_000_000_000_000_000_000;
+
+/// Unsigned integer of arbitrary size, used for arrangement counts that overflow `u64`.
+/// Limbs are stored least significant first in base 10^18 so printing stays trivial.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct BigCount {
+    limbs: Vec<u64>,
+}
+
+impl BigCount {
+    /// Returns zero.
+    pub fn zero() -> Self {
+        BigCount::default()
+    }
+
+    /// Returns one.
+    pub fn one() -> Self {
+        BigCount::from(1)
+    }
+
+    /// Checks whether the count is zero.
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    /// Converts the count to a `u128`, or `None` if it does not fit.
+    pub fn to_u128(&self) -> Option<u128> {
+        self.limbs.iter().rev().try_fold(0u128, |value, &limb| {
+            value
+                .checked_mul(LIMB_BASE as u128)?
+                .checked_add(limb as u128)
+        })
+    }
+}
+
+impl From<u64> for BigCount {
+    fn from(mut value: u64) -> Self {
+        let mut limbs = Vec::new();
+        while value > 0 {
+            limbs.push(value % LIMB_BASE);
+            value /= LIMB_BASE;
+        }
+        BigCount { limbs }
+    }
+}
+
+impl AddAssign<&BigCount> for BigCount {
+    fn add_assign(&mut self, other: &BigCount) {
+        if self.limbs.len() < other.limbs.len() {
+            self.limbs.resize(other.limbs.len(), 0);
+        }
+
+        let mut carry = 0;
+        for (index, limb) in self.limbs.iter_mut().enumerate() {
+            let sum = *limb + other.limbs.get(index).copied().unwrap_or(0) + carry;
+            *limb = sum % LIMB_BASE;
+            carry = sum / LIMB_BASE;
+            if carry == 0 && index >= other.limbs.len() {
+                break;
+            }
+        }
+        if carry > 0 {
+            self.limbs.push(carry);
+        }
+    }
+}
+
+impl Add<&BigCount> for BigCount {
+    type Output = BigCount;
+
+    fn add(mut self, other: &BigCount) -> BigCount {
+        self += other;
+        self
+    }
+}
+
+impl<'a> std::iter::Sum<&'a BigCount> for BigCount {
+    fn sum<I: Iterator<Item = &'a BigCount>>(iter: I) -> Self {
+        iter.fold(BigCount::zero(), |total, count| total + count)
+    }
+}
+
+impl std::iter::Sum for BigCount {
+    fn sum<I: Iterator<Item = BigCount>>(iter: I) -> Self {
+        iter.fold(BigCount::zero(), |total, count| total + &count)
+    }
+}
+
+impl fmt::Display for BigCount {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.limbs.split_last() {
+            None => write!(f, "0"),
+            Some((most, rest)) => {
+                write!(f, "{}", most)?;
+                for limb in rest.iter().rev() {
+                    write!(f, "{:018}", limb)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Aho–Corasick automaton over the towel patterns. A single pass over a design reports
+/// every pattern ending at each position, so counting arrangements is linear in the design
+/// length plus the number of matches.
+#[derive(Clone, Debug)]
+pub struct TowelMatcher {
+    /// Maps each byte to its column in `transitions`, or `None` if no pattern uses it.
+    alphabet: [Option<u8>; 256],
+    alphabet_len: usize,
+    /// Full transition table, `nodes x alphabet_len`, with failure links folded in.
+    transitions: Vec<usize>,
+    /// Length of the pattern ending at each node, if the node completes one.
+    pattern_len: Vec<Option<usize>>,
+    /// Nearest node along the failure links (excluding itself) that completes a pattern.
+    dictionary_link: Vec<Option<usize>>,
+    patterns: usize,
+}
+
+impl TowelMatcher {
+    const ROOT: usize = 0;
+
+    /// Builds the automaton. Empty and duplicate patterns are ignored.
+    pub fn new<I, S>(patterns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let patterns = patterns
+            .into_iter()
+            .map(|pattern| pattern.as_ref().trim().to_string())
+            .filter(|pattern| !pattern.is_empty())
+            .collect::<std::collections::BTreeSet<_>>();
+
+        let mut alphabet = [None; 256];
+        let mut alphabet_len = 0;
+        for &byte in patterns.iter().flat_map(|pattern| pattern.as_bytes()) {
+            if alphabet[byte as usize].is_none() {
+                alphabet[byte as usize] = Some(alphabet_len as u8);
+                alphabet_len += 1;
+            }
+        }
+
+        // Build the trie, using `usize::MAX` for missing edges
+        let mut transitions = vec![usize::MAX; alphabet_len];
+        let mut pattern_len = vec![None];
+        for pattern in &patterns {
+            let mut node = Self::ROOT;
+            for &byte in pattern.as_bytes() {
+                let slot = node * alphabet_len + alphabet[byte as usize].unwrap() as usize;
+                if transitions[slot] == usize::MAX {
+                    transitions[slot] = pattern_len.len();
+                    transitions.extend(std::iter::repeat_n(usize::MAX, alphabet_len));
+                    pattern_len.push(None);
+                }
+                node = transitions[slot];
+            }
+            pattern_len[node] = Some(pattern.len());
+        }
+
+        // Breadth-first pass computing failure links and completing the transitions
+        let nodes = pattern_len.len();
+        let mut failure = vec![Self::ROOT; nodes];
+        let mut dictionary_link = vec![None; nodes];
+        let mut queue = std::collections::VecDeque::new();
+        for slot in transitions[..alphabet_len].iter_mut() {
+            match *slot {
+                usize::MAX => *slot = Self::ROOT,
+                child => queue.push_back(child),
+            }
+        }
+        while let Some(node) = queue.pop_front() {
+            for column in 0..alphabet_len {
+                let slot = node * alphabet_len + column;
+                let fallback = transitions[failure[node] * alphabet_len + column];
+                match transitions[slot] {
+                    usize::MAX => transitions[slot] = fallback,
+                    child => {
+                        failure[child] = fallback;
+                        dictionary_link[child] = if pattern_len[fallback].is_some() {
+                            Some(fallback)
+                        } else {
+                            dictionary_link[fallback]
+                        };
+                        queue.push_back(child);
+                    }
+                }
+            }
+        }
+
+        TowelMatcher {
+            alphabet,
+            alphabet_len,
+            transitions,
+            pattern_len,
+            dictionary_link,
+            patterns: patterns.len(),
+        }
+    }
+
+    /// Returns the number of distinct towel patterns.
+    pub fn len(&self) -> usize {
+        self.patterns
+    }
+
+    /// Checks whether there are no towel patterns.
+    pub fn is_empty(&self) -> bool {
+        self.patterns == 0
+    }
+
+    /// Lists, for every position `end` in `0..=design.len()`, the lengths of the patterns
+    /// whose last stripe is just before `end`.
+    pub fn matches_ending(&self, design: &str) -> Vec<Vec<usize>> {
+        let mut ends = vec![Vec::new(); design.len() + 1];
+        let mut node = Self::ROOT;
+
+        for (index, &byte) in design.as_bytes().iter().enumerate() {
+            node = match self.alphabet[byte as usize] {
+                Some(column) => self.transitions[node * self.alphabet_len + column as usize],
+                None => Self::ROOT, // No pattern contains this stripe
+            };
+
+            let mut output = if self.pattern_len[node].is_some() {
+                Some(node)
+            } else {
+                self.dictionary_link[node]
+            };
+            while let Some(matched) = output {
+                ends[index + 1].push(self.pattern_len[matched].unwrap());
+                output = self.dictionary_link[matched];
+            }
+        }
+
+        ends
+    }
+
+    /// Counts the distinct ways to assemble `design` from the patterns.
+    pub fn count_arrangements(&self, design: &str) -> BigCount {
+        let ends = self.matches_ending(design);
+        let mut ways = vec![BigCount::zero(); design.len() + 1];
+        ways[0] = BigCount::one();
+
+        for end in 1..ways.len() {
+            let count = ends[end].iter().map(|&len| &ways[end - len]).sum();
+            ways[end] = count;
+        }
+
+        ways.pop().unwrap()
+    }
+
+    /// Checks whether `design` can be assembled at all.
+    pub fn is_possible(&self, design: &str) -> bool {
+        self.witness(design).is_some()
+    }
+
+    /// Returns one arrangement of `design`, or `None` if it is impossible.
+    pub fn witness<'d>(&self, design: &'d str) -> Option<Vec<&'d str>> {
+        let ends = self.matches_ending(design);
+        // Length of a pattern completing a reachable prefix at each position
+        let mut last_piece = vec![None; design.len() + 1];
+        last_piece[0] = Some(0);
+
+        for end in 1..last_piece.len() {
+            last_piece[end] = ends[end]
+                .iter()
+                .copied()
+                .find(|&len| last_piece[end - len].is_some());
+        }
+
+        last_piece[design.len()]?;
+        let mut pieces = Vec::new();
+        let mut end = design.len();
+        while end > 0 {
+            let len = last_piece[end].unwrap();
+            pieces.push(&design[end - len..end]);
+            end -= len;
+        }
+        pieces.reverse();
+        Some(pieces)
+    }
+
+    /// Lazily enumerates every arrangement of `design`, shorter first pieces first.
+    pub fn arrangements<'d>(&self, design: &'d str) -> Arrangements<'d> {
+        let ends = self.matches_ending(design);
+        let mut starts = vec![Vec::new(); design.len() + 1];
+        for (end, lens) in ends.iter().enumerate() {
+            for &len in lens {
+                starts[end - len].push(len);
+            }
+        }
+
+        // Prune pieces that lead to a suffix no arrangement can finish
+        let mut completes = vec![false; design.len() + 1];
+        completes[design.len()] = true;
+        for start in (0..design.len()).rev() {
+            starts[start].retain(|&len| completes[start + len]);
+            starts[start].sort_unstable();
+            completes[start] = !starts[start].is_empty();
+        }
+
+        Arrangements {
+            design,
+            stack: if completes[0] {
+                vec![(0, 0)]
+            } else {
+                Vec::new()
+            },
+            starts,
+        }
+    }
+}
+
+/// Iterator over the arrangements of one design, created by [`TowelMatcher::arrangements`].
+/// Only branches known to complete are explored, so every step yields promptly.
+#[derive(Clone, Debug)]
+pub struct Arrangements<'d> {
+    design: &'d str,
+    /// Usable piece lengths starting at each position.
+    starts: Vec<Vec<usize>>,
+    /// Depth-first frames of (position, index of the next piece to try).
+    stack: Vec<(usize, usize)>,
+}
+
+impl<'d> Iterator for Arrangements<'d> {
+    type Item = Vec<&'d str>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(&(position, choice)) = self.stack.last() {
+            if position == self.design.len() {
+                let pieces = self
+                    .stack
+                    .windows(2)
+                    .map(|pair| &self.design[pair[0].0..pair[1].0])
+                    .collect();
+                self.stack.pop();
+                return Some(pieces);
+            }
+
+            match self.starts[position].get(choice) {
+                Some(&len) => {
+                    self.stack.last_mut().unwrap().1 += 1;
+                    self.stack.push((position + len, 0));
+                }
+                None => {
+                    self.stack.pop();
+                }
+            }
+        }
+
+        None
+    }
+}
+
+/// Formats an arrangement as its pieces separated by `|`, e.g. `br|wr|r`.
+pub fn format_arrangement(pieces: &[&str]) -> String {
+    pieces.join("|")
+}
+
+/// Parses the towel patterns and the list of designs.
+pub fn parse_input(input: &str) -> (TowelMatcher, Vec<&str>) {
+    let (patterns, designs) = input.split_once("\n\n").unwrap_or(("", ""));
+    let matcher = TowelMatcher::new(patterns.split(','));
+    let designs = designs
+        .lines()
+        .map(str::trim)
+        .filter(|design| !design.is_empty())
+        .collect();
+
+    (matcher, designs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = r"r, wr, b, g, bwu, rb, gb, br
+
+brwrr
+bggr
+gbbr
+rrbgbr
+ubwu
+bwurrg
+brgr
+bbrgwb";
+
+    #[test]
+    fn test_big_count() {
+        let big = BigCount::from(u64::MAX);
+        assert_eq!((big.clone() + &big).to_string(), "36893488147419103230");
+        assert_eq!(BigCount::from(LIMB_BASE).to_string(), "1000000000000000000");
+        assert_eq!(BigCount::zero().to_string(), "0");
+        assert_eq!((big.clone() + &big).to_u128(), Some(2 * u64::MAX as u128));
+    }
+
+    #[test]
+    fn test_counts() {
+        let (matcher, designs) = parse_input(EXAMPLE);
+        let counts = designs
+            .iter()
+            .map(|design| matcher.count_arrangements(design).to_u128().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(counts, vec![2, 1, 4, 6, 0, 1, 2, 0]);
+
+        // Counts follow the Fibonacci numbers and soon overflow 128 bits
+        let matcher = TowelMatcher::new(["a", "aa"]);
+        let (mut previous, mut current) = (1u128, 1u128);
+        for _ in 1..150 {
+            (previous, current) = (current, previous + current);
+        }
+        assert_eq!(
+            matcher.count_arrangements(&"a".repeat(150)).to_u128(),
+            Some(current)
+        );
+        assert_eq!(matcher.count_arrangements(&"a".repeat(300)).to_u128(), None);
+        assert_eq!(
+            matcher
+                .count_arrangements(&"a".repeat(300))
+                .to_string()
+                .len(),
+            63
+        );
+    }
+
+    #[test]
+    fn test_overlapping_patterns() {
+        // Patterns that are suffixes of others are reported through dictionary links
+        let matcher = TowelMatcher::new(["abc", "bc", "c", "ab", "b", "a"]);
+        assert_eq!(matcher.matches_ending("abc")[3], vec![3, 2, 1]);
+        assert_eq!(matcher.count_arrangements("abc").to_u128(), Some(4));
+        assert!(!matcher.is_possible("abd"));
+
+        let matcher = TowelMatcher::new(["r", "r", " ", "wr"]);
+        assert_eq!(matcher.len(), 2);
+        assert_eq!(matcher.count_arrangements("wrr").to_u128(), Some(1));
+    }
+
+    #[test]
+    fn test_arrangements() {
+        let (matcher, _) = parse_input(EXAMPLE);
+        let listed = matcher
+            .arrangements("brwrr")
+            .map(|pieces| format_arrangement(&pieces))
+            .collect::<Vec<_>>();
+        assert_eq!(listed, vec!["b|r|wr|r", "br|wr|r"]);
+
+        assert_eq!(matcher.arrangements("rrbgbr").count(), 6);
+        assert_eq!(matcher.arrangements("ubwu").next(), None);
+
+        // Enumeration stays lazy even when there are astronomically many arrangements
+        let matcher = TowelMatcher::new(["a", "aa"]);
+        let design = "a".repeat(300);
+        let first = matcher.arrangements(&design).next().unwrap();
+        assert_eq!(first.len(), 300);
+    }
+
+    #[test]
+    fn test_witness() {
+        let (matcher, designs) = parse_input(EXAMPLE);
+        for design in designs {
+            match matcher.witness(design) {
+                Some(pieces) => assert_eq!(pieces.concat(), design),
+                None => assert!(matcher.count_arrangements(design).is_zero()),
+            }
+        }
+        assert_eq!(matcher.witness("bggr"), Some(vec!["b", "g", "g", "r"]));
+    }
+}
@@ -0,0 +1,487 @@
+use std::collections::{BTreeMap, HashSet, VecDeque};
+
+pub type Coord = (i32, i32); // Represents a coordinate (x, y) on the grid
+
+#[derive(Debug)]
+pub struct Race {
+    pub obstacles: HashSet<Coord>, // Set of coordinates representing obstacles (walls)
+    pub start: Coord,              // Starting point of the race
+    pub end: Coord,                // Ending point of the race
+}
+
+/// A cheat: the position just before collision is disabled, the track position where the
+/// program lands, and the picoseconds it saves over the normal route.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Cheat {
+    pub start: Coord,
+    pub end: Coord,
+    pub saved: i32,
+}
+
+/// Parses the input map into a `Race` structure containing obstacles, start, and end points.
+pub fn parse_race_map(input: &str) -> Race {
+    let mut start = (0, 0);
+    let mut end = (0, 0);
+
+    let mut obstacles: HashSet<Coord> = HashSet::new();
+
+    input.lines().enumerate().for_each(|(y, row)| {
+        row.chars().enumerate().for_each(|(x, tile)| match tile {
+            '#' => {
+                obstacles.insert((x as i32, y as i32)); // Add wall coordinates to obstacles set
+            }
+            'S' => {
+                start = (x as i32, y as i32); // Mark the starting point
+            }
+            'E' => {
+                end = (x as i32, y as i32); // Mark the ending point
+            }
+            _ => (), // Ignore empty spaces and other tiles
+        })
+    });
+
+    Race {
+        obstacles,
+        start,
+        end,
+    }
+}
+
+/// Performs a Breadth-First Search (BFS) from the start and returns every reachable track
+/// position with its distance, ordered by distance.
+pub fn track_distances(race: &Race) -> Vec<(Coord, i32)> {
+    let mut queue = VecDeque::from([(race.start, 0)]);
+    let mut visited = HashSet::from([race.start]);
+    let mut track = Vec::new();
+
+    while let Some((coord, cost)) = queue.pop_front() {
+        track.push((coord, cost));
+
+        for next_coord in [
+            (coord.0 - 1, coord.1), // Move left
+            (coord.0 + 1, coord.1), // Move right
+            (coord.0, coord.1 - 1), // Move up
+            (coord.0, coord.1 + 1), // Move down
+        ] {
+            // Only visit unvisited positions that are not obstacles
+            if !race.obstacles.contains(&next_coord) && visited.insert(next_coord) {
+                queue.push_back((next_coord, cost + 1));
+            }
+        }
+    }
+
+    track
+}
+
+/// Flat grid of track distances covering the race's bounding box, `-1` off the track.
+struct DistanceGrid {
+    min: Coord,
+    width: i32,
+    height: i32,
+    cells: Vec<i32>,
+}
+
+impl DistanceGrid {
+    fn new(track: &[(Coord, i32)]) -> Self {
+        let min = track
+            .iter()
+            .fold((i32::MAX, i32::MAX), |acc, &((x, y), _)| {
+                (acc.0.min(x), acc.1.min(y))
+            });
+        let max = track
+            .iter()
+            .fold((i32::MIN, i32::MIN), |acc, &((x, y), _)| {
+                (acc.0.max(x), acc.1.max(y))
+            });
+        let (width, height) = if track.is_empty() {
+            (0, 0)
+        } else {
+            (max.0 - min.0 + 1, max.1 - min.1 + 1)
+        };
+
+        let mut cells = vec![-1; (width * height) as usize];
+        for &((x, y), cost) in track {
+            cells[((y - min.1) * width + (x - min.0)) as usize] = cost;
+        }
+        DistanceGrid {
+            min,
+            width,
+            height,
+            cells,
+        }
+    }
+
+    fn get(&self, (x, y): Coord) -> Option<i32> {
+        let (x, y) = (x - self.min.0, y - self.min.1);
+        if x < 0 || y < 0 || x >= self.width || y >= self.height {
+            return None;
+        }
+        Some(self.cells[(y * self.width + x) as usize]).filter(|&cost| cost >= 0)
+    }
+}
+
+/// Finds every cheat lasting at most `duration` picoseconds that saves at least
+/// `min_saved` picoseconds, sorted by start and end position.
+pub fn find_cheats(race: &Race, duration: i32, min_saved: i32) -> Vec<Cheat> {
+    let track = track_distances(race);
+    let grid = DistanceGrid::new(&track);
+    let mut cheats = Vec::new();
+
+    // Scan the diamond of positions reachable within the cheat duration from each start
+    for &(start, start_cost) in &track {
+        for dy in -duration..=duration {
+            let span = duration - dy.abs();
+            for dx in -span..=span {
+                let end = (start.0 + dx, start.1 + dy);
+                let Some(end_cost) = grid.get(end) else {
+                    continue;
+                };
+                let saved = end_cost - start_cost - (dx.abs() + dy.abs());
+                if saved >= min_saved && saved > 0 {
+                    cheats.push(Cheat { start, end, saved });
+                }
+            }
+        }
+    }
+
+    cheats.sort();
+    cheats
+}
+
+/// Two-dimensional Fenwick tree counting points inserted at (u, v) cells.
+struct Fenwick2D {
+    size_u: usize,
+    size_v: usize,
+    tree: Vec<u32>,
+}
+
+impl Fenwick2D {
+    fn new(size_u: usize, size_v: usize) -> Self {
+        Fenwick2D {
+            size_u,
+            size_v,
+            tree: vec![0; size_u * size_v],
+        }
+    }
+
+    fn insert(&mut self, u: usize, v: usize) {
+        let mut i = u + 1;
+        while i <= self.size_u {
+            let mut j = v + 1;
+            while j <= self.size_v {
+                self.tree[(i - 1) * self.size_v + (j - 1)] += 1;
+                j += j & j.wrapping_neg();
+            }
+            i += i & i.wrapping_neg();
+        }
+    }
+
+    /// Counts the points with coordinates below `u` and `v`.
+    fn prefix(&self, u: usize, v: usize) -> usize {
+        let mut count = 0;
+        let mut i = u.min(self.size_u);
+        while i > 0 {
+            let mut j = v.min(self.size_v);
+            while j > 0 {
+                count += self.tree[(i - 1) * self.size_v + (j - 1)] as usize;
+                j -= j & j.wrapping_neg();
+            }
+            i -= i & i.wrapping_neg();
+        }
+        count
+    }
+
+    /// Counts the points inside the inclusive square `[u0, u1] x [v0, v1]`.
+    fn count(&self, u0: i64, u1: i64, v0: i64, v1: i64) -> usize {
+        let clamp_u = |u: i64| u.clamp(0, self.size_u as i64) as usize;
+        let clamp_v = |v: i64| v.clamp(0, self.size_v as i64) as usize;
+        let (u0, u1) = (clamp_u(u0), clamp_u(u1 + 1));
+        let (v0, v1) = (clamp_v(v0), clamp_v(v1 + 1));
+        if u0 >= u1 || v0 >= v1 {
+            return 0;
+        }
+        self.prefix(u1, v1) + self.prefix(u0, v0) - self.prefix(u0, v1) - self.prefix(u1, v0)
+    }
+}
+
+/// Counts the cheats lasting at most `duration` picoseconds that save at least
+/// `min_saved` picoseconds, without listing them.
+///
+/// In rotated coordinates `u = x + y`, `v = x - y` the Manhattan diamond becomes a square,
+/// and a pair whose distances differ by at least `min_saved + duration` saves enough as
+/// soon as it is within reach. Walking the track in distance order, those far pairs are
+/// counted by sliding earlier positions into a 2D Fenwick tree and querying the square;
+/// only the band of nearer pairs is checked one by one. This runs in
+/// O(n (log² w + duration)) instead of the O(n duration²) diamond scan.
+pub fn count_cheats(race: &Race, duration: i32, min_saved: i32) -> usize {
+    let track = track_distances(race);
+    if track.is_empty() || duration < 0 {
+        return 0;
+    }
+
+    let min_saved = min_saved.max(1) as i64; // A cheat has to save time to count
+    let radius = duration as i64;
+    let min_x = track.iter().map(|&((x, _), _)| x).min().unwrap() as i64;
+    let min_y = track.iter().map(|&((_, y), _)| y).min().unwrap() as i64;
+    let max_y = track.iter().map(|&((_, y), _)| y).max().unwrap() as i64;
+
+    // Rotated coordinates shifted to start at zero
+    let rotate = |(x, y): Coord| {
+        let (x, y) = (x as i64 - min_x, y as i64 - min_y);
+        (x + y, x - y + (max_y - min_y))
+    };
+    let points = track
+        .iter()
+        .map(|&(coord, cost)| (rotate(coord), cost as i64))
+        .collect::<Vec<_>>();
+    let size = points
+        .iter()
+        .map(|&((u, v), _)| u.max(v) as usize + 1)
+        .max()
+        .unwrap();
+
+    let mut tree = Fenwick2D::new(size, size);
+    let mut inserted = 0; // Positions far enough behind are in the tree
+    let mut band_start = 0; // First position of the near band
+    let mut count = 0;
+
+    for &((u, v), cost) in &points {
+        while inserted < points.len() && points[inserted].1 <= cost - min_saved - radius {
+            let ((pu, pv), _) = points[inserted];
+            tree.insert(pu as usize, pv as usize);
+            inserted += 1;
+        }
+        count += tree.count(u - radius, u + radius, v - radius, v + radius);
+
+        // Nearer positions must be checked against their exact distance
+        while band_start < points.len() && points[band_start].1 <= cost - min_saved - radius {
+            band_start += 1;
+        }
+        for &((pu, pv), start_cost) in &points[band_start..] {
+            if start_cost > cost - min_saved {
+                break;
+            }
+            let distance = (u - pu).abs().max((v - pv).abs());
+            if distance <= radius && cost - start_cost - distance >= min_saved {
+                count += 1;
+            }
+        }
+    }
+
+    count
+}
+
+/// Groups cheats by the number of picoseconds they save.
+pub fn cheat_histogram(cheats: &[Cheat]) -> BTreeMap<i32, usize> {
+    let mut histogram = BTreeMap::new();
+    for cheat in cheats {
+        *histogram.entry(cheat.saved).or_insert(0) += 1;
+    }
+    histogram
+}
+
+/// Formats a histogram with the sentences the puzzle text uses, smallest saving first.
+pub fn format_histogram(histogram: &BTreeMap<i32, usize>) -> String {
+    histogram
+        .iter()
+        .map(|(saved, &count)| match count {
+            1 => format!("- There is one cheat that saves `{}` picoseconds.\n", saved),
+            _ => format!(
+                "- There are `{}` cheats that save `{}` picoseconds.\n",
+                count, saved
+            ),
+        })
+        .collect()
+}
+
+/// Lists each cheat's start and end coordinates with the picoseconds it saves.
+pub fn format_cheats(cheats: &[Cheat]) -> String {
+    cheats
+        .iter()
+        .map(|cheat| {
+            format!(
+                "{:?} -> {:?} saves {} picoseconds\n",
+                cheat.start, cheat.end, cheat.saved
+            )
+        })
+        .collect()
+}
+
+/// Output selected on the command line after the input file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputMode {
+    Count,     // number of cheats saving at least the minimum
+    Histogram, // puzzle-style "N cheats save S picoseconds" lines
+    Cheats,    // start and end coordinates of every cheat
+}
+
+/// Options read from the command line: `[min_saved] [duration] [--histogram | --cheats]`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Options {
+    pub min_saved: i32,
+    pub duration: i32,
+    pub mode: OutputMode,
+}
+
+impl Options {
+    /// Parses the arguments that follow the input file, falling back to the defaults.
+    pub fn parse(args: &[String], min_saved: i32, duration: i32) -> Result<Options, String> {
+        let mut options = Options {
+            min_saved,
+            duration,
+            mode: OutputMode::Count,
+        };
+        let mut numbers = Vec::new();
+
+        for arg in args {
+            match arg.as_str() {
+                "--histogram" => options.mode = OutputMode::Histogram,
+                "--cheats" => options.mode = OutputMode::Cheats,
+                _ => numbers.push(
+                    arg.parse::<i32>()
+                        .map_err(|_| format!("Invalid argument: {}", arg))?,
+                ),
+            }
+        }
+
+        match numbers[..] {
+            [] => {}
+            [min_saved] => options.min_saved = min_saved,
+            [min_saved, duration] => {
+                options.min_saved = min_saved;
+                options.duration = duration;
+            }
+            _ => return Err("Too many numeric arguments".to_string()),
+        }
+        Ok(options)
+    }
+
+    /// Runs the solver and formats its output for the selected mode.
+    pub fn run(&self, race: &Race) -> String {
+        match self.mode {
+            OutputMode::Count => format!(
+                "result: {}\n",
+                count_cheats(race, self.duration, self.min_saved)
+            ),
+            OutputMode::Histogram => format_histogram(&cheat_histogram(&find_cheats(
+                race,
+                self.duration,
+                self.min_saved,
+            ))),
+            OutputMode::Cheats => format_cheats(&find_cheats(race, self.duration, self.min_saved)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static EXAMPLE: &str = r"###############
+#...#...#.....#
+#.#.#.#.#.###.#
+#S#...#.#.#...#
+#######.#.#.###
+#######.#.#...#
+#######.#.###.#
+###..E#...#...#
+###.#######.###
+#...###...#...#
+#.#####.#.###.#
+#.#...#.#.#...#
+#.#.#.#.#.#.###
+#...#...#...###
+###############";
+
+    /// Collects the "- There ..." histogram lines from a puzzle description.
+    fn histogram_lines(doc: &str) -> String {
+        doc.lines()
+            .filter(|line| line.starts_with("- There"))
+            .map(|line| format!("{}\n", line))
+            .collect()
+    }
+
+    #[test]
+    fn test_histogram_matches_docs() {
+        let race = parse_race_map(EXAMPLE);
+
+        let part_1 = cheat_histogram(&find_cheats(&race, 2, 1));
+        let doc = std::fs::read_to_string("docs/challenge.md").unwrap();
+        assert_eq!(format_histogram(&part_1), histogram_lines(&doc));
+
+        let part_2 = cheat_histogram(&find_cheats(&race, 20, 50));
+        let doc = std::fs::read_to_string("docs/challenge_2.md").unwrap();
+        assert_eq!(format_histogram(&part_2), histogram_lines(&doc));
+    }
+
+    #[test]
+    fn test_find_cheats() {
+        let race = parse_race_map(EXAMPLE);
+
+        // The 64 picosecond cheat goes from just above the wall straight to the end
+        assert_eq!(
+            find_cheats(&race, 2, 64),
+            vec![Cheat {
+                start: (7, 7),
+                end: (5, 7),
+                saved: 64
+            }]
+        );
+        assert_eq!(count_cheats(&race, 2, 38), 3);
+        assert_eq!(count_cheats(&race, 20, 76), 3);
+    }
+
+    #[test]
+    fn test_count_matches_listing() {
+        let race = parse_race_map(EXAMPLE);
+        for duration in [0, 1, 2, 3, 6, 20, 40, 100] {
+            for min_saved in [-5, 0, 1, 2, 10, 50, 76, 85] {
+                assert_eq!(
+                    count_cheats(&race, duration, min_saved),
+                    find_cheats(&race, duration, min_saved).len(),
+                    "duration {} min_saved {}",
+                    duration,
+                    min_saved
+                );
+            }
+        }
+
+        // Open areas give many positions the same distance
+        let open = parse_race_map("#######\n#S....#\n#.##..#\n#....E#\n#######");
+        for duration in 0..6 {
+            for min_saved in 0..4 {
+                assert_eq!(
+                    count_cheats(&open, duration, min_saved),
+                    find_cheats(&open, duration, min_saved).len()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_count_matches_listing_on_input() {
+        let race = parse_race_map(&std::fs::read_to_string("docs/challenge_2.txt").unwrap());
+        for (duration, min_saved) in [(2, 100), (20, 100), (30, 50)] {
+            assert_eq!(
+                count_cheats(&race, duration, min_saved),
+                find_cheats(&race, duration, min_saved).len()
+            );
+        }
+    }
+
+    #[test]
+    fn test_options() {
+        let args = |list: &[&str]| list.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+
+        let options = Options::parse(&args(&[]), 100, 2).unwrap();
+        assert_eq!((options.min_saved, options.duration), (100, 2));
+        assert_eq!(options.mode, OutputMode::Count);
+
+        let options = Options::parse(&args(&["50", "20", "--histogram"]), 100, 2).unwrap();
+        assert_eq!((options.min_saved, options.duration), (50, 20));
+        assert_eq!(options.mode, OutputMode::Histogram);
+
+        assert!(Options::parse(&args(&["--graph"]), 100, 2).is_err());
+        assert!(Options::parse(&args(&["1", "2", "3"]), 100, 2).is_err());
+    }
+}
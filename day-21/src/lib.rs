@@ -0,0 +1,513 @@
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+
+/// Layout of the numeric keypad on the door (a space marks the gap).
+pub const NUMERIC_LAYOUT: &str = "789\n456\n123\n 0A";
+
+/// Layout of the directional keypad used to control a robot (a space marks the gap).
+pub const DIRECTIONAL_LAYOUT: &str = " ^A\n<v>";
+
+/// Keys every control keypad needs so that it can drive the robot arm below it.
+const CONTROL_KEYS: [u8; 5] = [b'^', b'v', b'<', b'>', b'A'];
+
+type PositionT = (usize, usize);
+
+/// A keypad of any shape. Cells holding a space (or missing at the end of a short row)
+/// are gaps that a robot arm must never point at.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Keypad {
+    rows: Vec<Vec<u8>>,                // key by row and column, `b' '` for gaps
+    positions: HashMap<u8, PositionT>, // (x, y) position of each key
+}
+
+impl Keypad {
+    /// Parses a keypad layout, one row per line. Every key must be unique and the
+    /// keypad must have an `A` key, where the arm starts.
+    pub fn parse(layout: &str) -> Result<Keypad, String> {
+        let rows = layout
+            .lines()
+            .map(|line| line.bytes().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+
+        let mut positions = HashMap::new();
+        for (y, row) in rows.iter().enumerate() {
+            for (x, &key) in row.iter().enumerate() {
+                if key == b' ' {
+                    continue;
+                }
+                if positions.insert(key, (x, y)).is_some() {
+                    return Err(format!("Duplicate key '{}' in keypad", key as char));
+                }
+            }
+        }
+
+        if !positions.contains_key(&b'A') {
+            return Err("Keypad has no 'A' key".to_string());
+        }
+        Ok(Keypad { rows, positions })
+    }
+
+    /// Returns the numeric door keypad from the puzzle.
+    pub fn numeric() -> Keypad {
+        Keypad::parse(NUMERIC_LAYOUT).unwrap()
+    }
+
+    /// Returns the directional keypad from the puzzle.
+    pub fn directional() -> Keypad {
+        Keypad::parse(DIRECTIONAL_LAYOUT).unwrap()
+    }
+
+    /// Returns the (x, y) position of a key.
+    pub fn position(&self, key: u8) -> Option<PositionT> {
+        self.positions.get(&key).copied()
+    }
+
+    /// Returns the key at a position, or `None` for gaps and positions off the keypad.
+    pub fn key_at(&self, x: i64, y: i64) -> Option<u8> {
+        if x < 0 || y < 0 {
+            return None;
+        }
+        self.rows
+            .get(y as usize)
+            .and_then(|row| row.get(x as usize))
+            .copied()
+            .filter(|&key| key != b' ')
+    }
+
+    /// Checks whether a position lies within the keypad's bounding box, gaps included.
+    pub fn contains(&self, x: i64, y: i64) -> bool {
+        let width = self.rows.iter().map(Vec::len).max().unwrap_or(0);
+        x >= 0 && y >= 0 && (x as usize) < width && (y as usize) < self.rows.len()
+    }
+
+    /// Lists every shortest sequence of arm moves (`^`, `v`, `<`, `>`) from one key to
+    /// another that avoids the gaps, each followed by the final `A` press.
+    pub fn paths(&self, from: u8, to: u8) -> Vec<Vec<u8>> {
+        let (Some(start), Some(end)) = (self.position(from), self.position(to)) else {
+            return Vec::new();
+        };
+
+        // Distance of every cell to the target key, searching backwards from it
+        let mut distances = HashMap::from([(end, 0)]);
+        let mut queue = VecDeque::from([end]);
+        while let Some((x, y)) = queue.pop_front() {
+            for (_, nx, ny) in moves(x, y) {
+                if self.key_at(nx, ny).is_some()
+                    && !distances.contains_key(&(nx as usize, ny as usize))
+                {
+                    distances.insert((nx as usize, ny as usize), distances[&(x, y)] + 1);
+                    queue.push_back((nx as usize, ny as usize));
+                }
+            }
+        }
+
+        // Keys cut off from each other by gaps have no path at all
+        if !distances.contains_key(&start) {
+            return Vec::new();
+        }
+
+        // Walk forwards from the start, only stepping closer to the target
+        let mut paths = Vec::new();
+        let mut stack = vec![(start, Vec::new())];
+        while let Some(((x, y), mut path)) = stack.pop() {
+            if (x, y) == end {
+                path.push(b'A');
+                paths.push(path);
+                continue;
+            }
+            for (direction, nx, ny) in moves(x, y) {
+                let next = (nx as usize, ny as usize);
+                if nx >= 0 && ny >= 0 && distances.get(&next) == Some(&(distances[&(x, y)] - 1)) {
+                    let mut next_path = path.clone();
+                    next_path.push(direction);
+                    stack.push((next, next_path));
+                }
+            }
+        }
+
+        paths.sort();
+        paths
+    }
+}
+
+/// Returns the four arm moves from a position with the key that triggers each of them.
+fn moves(x: usize, y: usize) -> [(u8, i64, i64); 4] {
+    let (x, y) = (x as i64, y as i64);
+    [
+        (b'^', x, y - 1),
+        (b'v', x, y + 1),
+        (b'<', x - 1, y),
+        (b'>', x + 1, y),
+    ]
+}
+
+/// Reasons a replayed keypress sequence can fail. Presses are counted from zero in the
+/// top-level sequence and layers from zero at the door keypad.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ReplayError {
+    Gap {
+        press: usize,
+        layer: usize,
+        position: (i64, i64),
+    }, // An arm points at an empty cell
+    OffPad {
+        press: usize,
+        layer: usize,
+        position: (i64, i64),
+    }, // An arm leaves the keypad
+    InvalidKey {
+        press: usize,
+        key: char,
+    }, // The sequence holds something other than `<^>vA`
+    NoKeypads, // The chain has no keypad to type on
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::Gap {
+                press,
+                layer,
+                position,
+            } => write!(
+                f,
+                "press {}: arm over keypad {} points at the gap at {:?}",
+                press, layer, position
+            ),
+            ReplayError::OffPad {
+                press,
+                layer,
+                position,
+            } => write!(
+                f,
+                "press {}: arm over keypad {} leaves the pad at {:?}",
+                press, layer, position
+            ),
+            ReplayError::InvalidKey { press, key } => {
+                write!(f, "press {}: '{}' is not a directional key", press, key)
+            }
+            ReplayError::NoKeypads => write!(f, "there is no keypad to replay presses on"),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+/// Replays top-level presses through a chain of keypads, layer by layer, and returns the
+/// code typed on the door keypad (`keypads[0]`). Every arm starts on its `A` key and the
+/// presses steer the arm over the last keypad.
+pub fn replay(keypads: &[Keypad], presses: &str) -> Result<String, ReplayError> {
+    if keypads.is_empty() {
+        return Err(ReplayError::NoKeypads);
+    }
+
+    let mut arms = keypads
+        .iter()
+        .map(|keypad| {
+            let (x, y) = keypad.position(b'A').unwrap();
+            (x as i64, y as i64)
+        })
+        .collect::<Vec<_>>();
+    let mut typed = String::new();
+
+    for (press, key) in presses.bytes().enumerate() {
+        let mut layer = keypads.len() - 1;
+        let mut key = key;
+
+        // An `A` pushes the key under this arm down to the layer below, a move stops here
+        loop {
+            let (x, y) = arms[layer];
+            let position = match key {
+                b'^' => (x, y - 1),
+                b'v' => (x, y + 1),
+                b'<' => (x - 1, y),
+                b'>' => (x + 1, y),
+                b'A' => {
+                    let pressed = keypads[layer].key_at(x, y).unwrap();
+                    if layer == 0 {
+                        typed.push(pressed as char);
+                        break;
+                    }
+                    layer -= 1;
+                    key = pressed;
+                    continue;
+                }
+                _ => {
+                    return Err(ReplayError::InvalidKey {
+                        press,
+                        key: key as char,
+                    });
+                }
+            };
+
+            if keypads[layer].key_at(position.0, position.1).is_none() {
+                return Err(if keypads[layer].contains(position.0, position.1) {
+                    ReplayError::Gap {
+                        press,
+                        layer,
+                        position,
+                    }
+                } else {
+                    ReplayError::OffPad {
+                        press,
+                        layer,
+                        position,
+                    }
+                });
+            }
+            arms[layer] = position;
+            break;
+        }
+    }
+
+    Ok(typed)
+}
+
+/// A chain of keypads. The first keypad is the one whose keys must be typed (the door);
+/// each later keypad is pressed by a robot to steer the arm over the previous one, and
+/// the top-level presses are made by hand on one more control keypad.
+#[derive(Clone, Debug)]
+pub struct RobotChain {
+    keypads: Vec<Keypad>,
+    cache: HashMap<(usize, u8, u8), (usize, usize)>, // (cost, index of the best path)
+}
+
+impl RobotChain {
+    /// Builds a chain from the door keypad followed by the control keypads, in order
+    /// from the door towards the human.
+    pub fn from_keypads(keypads: Vec<Keypad>) -> Result<RobotChain, String> {
+        if keypads.is_empty() {
+            return Err("A robot chain needs at least a door keypad".to_string());
+        }
+        for (layer, keypad) in keypads.iter().enumerate().skip(1) {
+            if let Some(&key) = CONTROL_KEYS
+                .iter()
+                .find(|&&key| keypad.position(key).is_none())
+            {
+                return Err(format!(
+                    "Control keypad {} is missing the '{}' key",
+                    layer, key as char
+                ));
+            }
+        }
+        Ok(RobotChain {
+            keypads,
+            cache: HashMap::new(),
+        })
+    }
+
+    /// Builds a chain where `robots` intermediate robots each use a copy of `control`.
+    pub fn new(door: Keypad, control: Keypad, robots: usize) -> Result<RobotChain, String> {
+        let mut keypads = vec![door];
+        keypads.extend(std::iter::repeat_n(control, robots));
+        RobotChain::from_keypads(keypads)
+    }
+
+    /// Returns the keypads of the chain, starting with the door keypad.
+    pub fn keypads(&self) -> &[Keypad] {
+        &self.keypads
+    }
+
+    /// Memoised cost, in top-level presses, of moving the arm over keypad `layer` from
+    /// one key to another and pressing it. Also returns which of the shortest paths
+    /// between the keys achieves that cost.
+    fn move_cost(&mut self, layer: usize, from: u8, to: u8) -> (usize, usize) {
+        if let Some(&cached) = self.cache.get(&(layer, from, to)) {
+            return cached;
+        }
+
+        let paths = self.keypads[layer].paths(from, to);
+        let mut best = (usize::MAX, 0);
+        for (index, path) in paths.iter().enumerate() {
+            // Presses above the last keypad are made by hand and cost one each
+            let cost = if layer + 1 == self.keypads.len() {
+                path.len()
+            } else {
+                self.sequence_cost(layer + 1, path)
+            };
+            if cost < best.0 {
+                best = (cost, index);
+            }
+        }
+
+        self.cache.insert((layer, from, to), best);
+        best
+    }
+
+    /// Cost of typing `keys` on keypad `layer`, with its arm starting on `A`.
+    fn sequence_cost(&mut self, layer: usize, keys: &[u8]) -> usize {
+        let mut from = b'A';
+        let mut total = 0;
+        for &key in keys {
+            total = self.move_cost(layer, from, key).0.saturating_add(total);
+            from = key;
+        }
+        total
+    }
+
+    /// Expands `keys` typed on keypad `layer` into the optimal top-level presses.
+    fn expand(&mut self, layer: usize, keys: &[u8], output: &mut Vec<u8>) {
+        let mut from = b'A';
+        for &key in keys {
+            let (_, index) = self.move_cost(layer, from, key);
+            let path = self.keypads[layer].paths(from, key).swap_remove(index);
+            if layer + 1 == self.keypads.len() {
+                output.extend(path);
+            } else {
+                self.expand(layer + 1, &path, output);
+            }
+            from = key;
+        }
+    }
+
+    /// Returns the length of the shortest top-level sequence that types `code` on the
+    /// door keypad, or `None` if the code uses a key the door keypad does not have or
+    /// a key that the arm cannot reach.
+    pub fn sequence_length(&mut self, code: &str) -> Option<usize> {
+        let keys = code.as_bytes();
+        if keys
+            .iter()
+            .any(|&key| self.keypads[0].position(key).is_none())
+        {
+            return None;
+        }
+        Some(self.sequence_cost(0, keys)).filter(|&cost| cost != usize::MAX)
+    }
+
+    /// Returns one shortest top-level sequence that types `code` on the door keypad.
+    /// Its length grows exponentially with the number of robots, so prefer
+    /// [`RobotChain::sequence_length`] for long chains.
+    pub fn shortest_sequence(&mut self, code: &str) -> Option<String> {
+        self.sequence_length(code)?;
+        let mut output = Vec::new();
+        self.expand(0, code.as_bytes(), &mut output);
+        Some(String::from_utf8(output).unwrap())
+    }
+
+    /// Returns the complexity of a code: its shortest sequence length times the number
+    /// formed by the code's digits.
+    pub fn complexity(&mut self, code: &str) -> Option<usize> {
+        let digits = code
+            .chars()
+            .filter(char::is_ascii_digit)
+            .collect::<String>();
+        let numeric_part = digits.parse::<usize>().unwrap_or(0);
+        Some(self.sequence_length(code)? * numeric_part)
+    }
+
+    /// Replays top-level presses through this chain and returns the code they type.
+    pub fn replay(&self, presses: &str) -> Result<String, ReplayError> {
+        replay(&self.keypads, presses)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keypad_parse() {
+        let keypad = Keypad::numeric();
+        assert_eq!(keypad.position(b'A'), Some((2, 3)));
+        assert_eq!(keypad.key_at(0, 3), None);
+        assert_eq!(keypad.key_at(3, 0), None);
+
+        assert!(Keypad::parse("AA").is_err());
+        assert!(Keypad::parse("12\n3").is_err());
+
+        // Shortest paths never cross the gap in the bottom-left corner
+        assert_eq!(
+            keypad.paths(b'A', b'1'),
+            vec![b"<^<A".to_vec(), b"^<<A".to_vec()]
+        );
+        assert_eq!(keypad.paths(b'5', b'5'), vec![b"A".to_vec()]);
+    }
+
+    #[test]
+    fn test_shortest_sequence() {
+        let mut chain = RobotChain::new(Keypad::numeric(), Keypad::directional(), 2).unwrap();
+
+        let sequence = chain.shortest_sequence("029A").unwrap();
+        assert_eq!(sequence.len(), 68);
+        assert_eq!(chain.sequence_length("029A"), Some(68));
+        assert_eq!(chain.complexity("179A"), Some(68 * 179));
+        assert_eq!(chain.sequence_length("02B"), None);
+
+        // With no robots the human steers the door's arm directly: <A^A>^^AvvvA
+        let mut direct = RobotChain::new(Keypad::numeric(), Keypad::directional(), 0).unwrap();
+        assert_eq!(direct.shortest_sequence("029A").unwrap().len(), 12);
+    }
+
+    #[test]
+    fn test_replay() {
+        assert_eq!(replay(&[], "<A"), Err(ReplayError::NoKeypads));
+        assert_eq!(replay(&[], ""), Err(ReplayError::NoKeypads));
+
+        let chain = RobotChain::new(Keypad::numeric(), Keypad::directional(), 2).unwrap();
+        let sequence = "<vA<AA>>^AvAA<^A>A<v<A>>^AvA^A<vA>^A<v<A>^A>AAvA^A<v<A>A>^AAAvA<^A>A";
+        assert_eq!(chain.replay(sequence), Ok("029A".to_string()));
+
+        // The door arm starts on A, so a single robot moving it left twice hits the gap
+        let direct = RobotChain::new(Keypad::numeric(), Keypad::directional(), 0).unwrap();
+        assert_eq!(direct.replay("<A^A"), Ok("02".to_string()));
+        assert_eq!(
+            direct.replay("<<"),
+            Err(ReplayError::Gap {
+                press: 1,
+                layer: 0,
+                position: (0, 3)
+            })
+        );
+        assert_eq!(
+            direct.replay("v"),
+            Err(ReplayError::OffPad {
+                press: 0,
+                layer: 0,
+                position: (2, 4)
+            })
+        );
+
+        // Pressing `<` twice through the top keypad walks the robot below into its gap
+        assert_eq!(
+            chain.replay("v<<AA"),
+            Err(ReplayError::Gap {
+                press: 4,
+                layer: 1,
+                position: (0, 0)
+            })
+        );
+        assert_eq!(
+            chain.replay("Ax"),
+            Err(ReplayError::InvalidKey { press: 1, key: 'x' })
+        );
+    }
+
+    #[test]
+    fn test_shortest_sequence_replays() {
+        let layouts = [NUMERIC_LAYOUT, "123\n4 5\n67A"];
+        for layout in layouts {
+            for robots in 0..=3 {
+                let door = Keypad::parse(layout).unwrap();
+                let mut chain = RobotChain::new(door, Keypad::directional(), robots).unwrap();
+                for code in ["029A", "980A", "179A", "456A", "379A", "27A"] {
+                    if let Some(sequence) = chain.shortest_sequence(code) {
+                        assert_eq!(chain.replay(&sequence), Ok(code.to_string()));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_custom_layout() {
+        // A ring-shaped door keypad forces paths around the central gap
+        let door = Keypad::parse("123\n4 5\n67A").unwrap();
+        let mut chain = RobotChain::new(door, Keypad::directional(), 1).unwrap();
+
+        assert_eq!(chain.keypads()[0].paths(b'2', b'7').len(), 2);
+        assert!(chain.sequence_length("27A").is_some());
+
+        let incomplete = Keypad::parse("^A\n<>").unwrap();
+        assert!(RobotChain::new(Keypad::numeric(), incomplete, 1).is_err());
+    }
+}
@@ -1,3 +1,5 @@
+use day_22::{nth_secrets, parse_seeds};
+
 /// Reads the input file from command-line arguments
 pub fn read_file_from_args() -> String {
     let args: Vec<String> = std::env::args().collect(); // Collect command-line arguments into a vector
@@ -16,22 +18,19 @@ pub fn read_file_from_path(path: &str) -> String {
 }
 
 /// Calculate the sum of the 2000th secret number for all initial values
-pub fn solve(input: &str) -> isize {
-    input
-        .lines() // Iterate over each line in the input
-        .filter_map(|line| line.parse::<isize>().ok()) // Parse each line into a number, ignoring invalid lines
-        .map(|initial_value| calculate_nth_secret(initial_value, 2000)) // Compute the 2000th secret number
-        .sum::<isize>() // Sum all computed values
+pub fn solve(input: &str) -> u64 {
+    let seeds = parse_seeds(input); // Parse each line into a number, ignoring invalid lines
+
+    nth_secrets(&seeds, 2000) // Compute the 2000th secret numbers, one batch of buyers at a time
+        .into_iter()
+        .map(u64::from)
+        .sum() // Sum all computed values
 }
 
-/// Computes the nth secret number for a given initial value
-fn calculate_nth_secret(initial_value: isize, steps: isize) -> isize {
-    let calc_nth_secret = |mut s: isize| -> isize {
-        s = ((s * 64) ^ s) % 16_777_216; // Step 1: Multiply, XOR, and prune
-        s = ((s / 32) ^ s) % 16_777_216; // Step 2: Divide, XOR, and prune
-        ((s * 2048) ^ s) % 16_777_216 // Step 3: Multiply, XOR, and prune
-    };
-    (0..steps).fold(initial_value, |current, _| calc_nth_secret(current)) // Apply the evolution function repeatedly
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let input = &read_file_from_args(); // Include the input data as a string
+    println!("result {}", solve(input)); // Compute and print Part 1 result
+    Ok(())
 }
 
 #[cfg(test)]
@@ -55,9 +54,3 @@ mod tests {
         assert_eq!(solve(&input), 19241711734); // Verify the result for Part 1
     }
 }
-
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let input = &read_file_from_args(); // Include the input data as a string
-    println!("result {}", solve(input)); // Compute and print Part 1 result
-    Ok(())
-}
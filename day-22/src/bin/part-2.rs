@@ -1,16 +1,14 @@
-use std::{
-    collections::HashMap,
-    iter::once,
-    sync::{Arc, Mutex},
-    thread,
-};
+use day_22::{first_sale, parse_seeds, prices_csv, top_windows, window_totals};
 
 /// Reads the input file from command-line arguments
 pub fn read_file_from_args() -> String {
     let args: Vec<String> = std::env::args().collect(); // Collect command-line arguments into a vector
     if args.len() < 2 {
         // Check if the file path is provided
-        eprintln!("Usage: {} <input_file>", args[0]); // Print usage if the file argument is missing
+        eprintln!(
+            "Usage: {} <input_file> [--report [top_n] | --sequence a,b,c,d | --csv]",
+            args[0]
+        ); // Print usage if the file argument is missing
         std::process::exit(1); // Exit if the argument is missing
     }
 
@@ -22,78 +20,110 @@ pub fn read_file_from_path(path: &str) -> String {
     std::fs::read_to_string(path).expect("Failed to read input file") // Read file content as string
 }
 
-/// Find the sequence of price changes to maximize sales (faster than the original single threaded fn)
-pub fn solve(input: &str) -> Result<isize, Box<dyn std::error::Error>> {
-    let lines = input
-        .lines() // Split input into lines
-        .map(|line| line.to_owned()) // Clone each line into a string
-        .collect::<Vec<String>>(); // Collect lines into a vector
-    let shared_counts = Arc::new(Mutex::new(HashMap::new())); // Shared hashmap to store sequences and counts
-
-    let num_threads = std::thread::available_parallelism()?.get().max(1); // Determine the number of threads
-    let chunk_size = (lines.len() / num_threads).max(1); // Calculate the chunk size, ensuring it is at least 1
-    let chunks = lines.chunks(chunk_size); // Split the input lines into chunks
-
-    // Spawn threads to process each chunk
-    let thread_handles: Vec<_> = chunks
-        .map(|chunk| {
-            let chunk_clone = chunk.to_owned(); // Clone the chunk for thread processing
-            let shared_counts_clone = Arc::clone(&shared_counts); // Clone the shared hashmap reference
-            thread::spawn(move || {
-                let mut local_counts = HashMap::new(); // Local hashmap for this thread
-                for line in chunk_clone {
-                    if let Ok(initial_value) = line.parse::<isize>() {
-                        for (sequence, value) in compute_sequences(initial_value, 2000) {
-                            *local_counts.entry(sequence).or_insert(0) += value; // Update local counts
-                        }
-                    }
-                }
-                let mut global_counts = shared_counts_clone.lock().unwrap(); // Lock the shared hashmap
-                for (sequence, value) in local_counts {
-                    *global_counts.entry(sequence).or_insert(0) += value; // Merge local counts into shared hashmap
-                }
-            })
-        })
-        .collect();
-
-    for handle in thread_handles {
-        handle.join().unwrap(); // Wait for all threads to complete
+/// Find the sequence of price changes to maximize sales, using a flat array indexed by the
+/// packed window of four changes instead of a map of heap-allocated sequences
+pub fn solve(input: &str) -> Result<u32, Box<dyn std::error::Error>> {
+    let seeds = parse_seeds(input); // Parse the initial secret of each buyer
+    let totals = window_totals(&seeds, 2000); // Sum the first sale of each buyer per window
+
+    let max_value = totals
+        .into_iter()
+        .max()
+        .filter(|_| !seeds.is_empty())
+        .ok_or("No maximum value found")?; // Find the maximum value across all windows
+    Ok(max_value) // Return the maximum value
+}
+
+/// Parses a comma-separated window of four price changes such as `-2,1,-1,3`
+fn parse_sequence(text: &str) -> Result<[i8; 4], Box<dyn std::error::Error>> {
+    let changes = text
+        .split(',')
+        .map(|change| change.trim().parse::<i8>())
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let changes: [i8; 4] = changes
+        .try_into()
+        .map_err(|_| "A sequence needs exactly four price changes")?;
+    if changes.iter().any(|change| !(-9..=9).contains(change)) {
+        return Err("Price changes must be between -9 and 9".into());
+    }
+    Ok(changes)
+}
+
+/// Formats a comma-separated window of price changes
+fn format_sequence(changes: [i8; 4]) -> String {
+    changes.map(|change| change.to_string()).join(",")
+}
+
+/// Lists, for one window of changes, when and at which price each buyer sells
+fn sales_report(seeds: &[u32], changes: [i8; 4]) -> String {
+    let mut report = format!("sequence {}\n", format_sequence(changes));
+    report.push_str(&format!(
+        "{:>6}  {:>10}  {:>5}  {:>5}\n",
+        "buyer", "seed", "step", "price"
+    ));
+
+    let mut total = 0;
+    for (buyer, &seed) in seeds.iter().enumerate() {
+        let (step, price) = match first_sale(seed, changes, 2000) {
+            Some((step, price)) => {
+                total += price as u32;
+                (step.to_string(), price.to_string())
+            }
+            None => ("-".to_string(), "-".to_string()), // The monkey never sells to this buyer
+        };
+        report.push_str(&format!(
+            "{:>6}  {:>10}  {:>5}  {:>5}\n",
+            buyer + 1,
+            seed,
+            step,
+            price
+        ));
     }
 
-    let counts = shared_counts.lock().unwrap(); // Lock the shared hashmap
-    let max_value = counts.values().max().ok_or("No maximum value found")?; // Find the maximum value in the hashmap
-    Ok(*max_value) // Return the maximum value
+    report.push_str(&format!("total: {}\n", total));
+    report
 }
 
-/// Computes sequences of price changes over n steps and their corresponding values
-fn compute_sequences(initial_value: isize, steps: isize) -> HashMap<Vec<isize>, isize> {
-    let step = |mut s| -> isize {
-        s = ((s * 64) ^ s) % 16_777_216; // Step 1: Multiply, XOR, and prune
-        s = ((s / 32) ^ s) % 16_777_216; // Step 2: Divide, XOR, and prune
-        ((s * 2048) ^ s) % 16_777_216 // Step 3: Multiply, XOR, and prune
-    };
-
-    let prices = once(initial_value % 10) // Start with the initial price
-        .chain((0..steps).scan(initial_value, |state, _| {
-            let next_value = step(*state); // Compute the next secret number
-            *state = next_value; // Update the state
-            Some(next_value % 10) // Compute the next price
-        }))
-        .collect::<Vec<isize>>(); // Collect all prices into a vector
-
-    let price_differences = prices
-        .windows(2) // Create sliding windows of size 2
-        .map(|window| window[1] - window[0]) // Calculate the difference between consecutive prices
-        .collect::<Vec<isize>>(); // Collect differences into a vector
-
-    price_differences
-        .windows(4) // Create sliding windows of size 4
-        .enumerate() // Enumerate the windows with their indices
-        .fold(HashMap::new(), |mut sequences, (index, window)| {
-            let sequence_key = window.to_vec(); // Convert the window into a vector
-            sequences.entry(sequence_key).or_insert(prices[index + 4]);
-            sequences
-        })
+/// Ranks the `top_n` best windows of changes, followed by the per-buyer sales of the best one
+fn market_report(input: &str, top_n: usize) -> Result<String, Box<dyn std::error::Error>> {
+    let seeds = parse_seeds(input);
+    let ranked = top_windows(&window_totals(&seeds, 2000), top_n);
+    let &(best, _) = ranked.first().ok_or("No maximum value found")?;
+
+    let mut report = format!("{:>4}  {:<12}  {:>7}\n", "rank", "changes", "bananas");
+    for (rank, (changes, total)) in ranked.iter().enumerate() {
+        report.push_str(&format!(
+            "{:>4}  {:<12}  {:>7}\n",
+            rank + 1,
+            format_sequence(*changes),
+            total
+        ));
+    }
+
+    report.push('\n');
+    report.push_str(&sales_report(&seeds, best));
+    Ok(report)
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let input = &read_file_from_args(); // Include the input data as a string
+    let args: Vec<String> = std::env::args().collect();
+
+    match args.get(2).map(String::as_str) {
+        None => println!("result: {}", solve(input)?), // Compute and print Part 2 result
+        Some("--report") => {
+            let top_n = args.get(3).map_or(Ok(10), |arg| arg.parse())?;
+            print!("{}", market_report(input, top_n)?);
+        }
+        Some("--sequence") => {
+            let changes = parse_sequence(args.get(3).ok_or("Missing sequence, e.g. -2,1,-1,3")?)?;
+            print!("{}", sales_report(&parse_seeds(input), changes));
+        }
+        Some("--csv") => print!("{}", prices_csv(&parse_seeds(input), 2000)),
+        Some(other) => return Err(format!("Unknown option: {}", other).into()),
+    }
+    Ok(())
 }
 
 #[cfg(test)]
@@ -111,14 +141,40 @@ mod tests {
         assert_eq!(result, 23);
     }
 
+    #[test]
+    fn test_market_report() {
+        let input = r#"1
+2
+3
+2024"#;
+
+        let expected = r#"rank  changes       bananas
+   1  -2,1,-1,3          23
+   2  -1,3,1,0           22
+
+sequence -2,1,-1,3
+ buyer        seed   step  price
+     1           1   1964      7
+     2           2    291      7
+     3           3      -      -
+     4        2024    455      9
+total: 23
+"#;
+        assert_eq!(market_report(input, 2).unwrap(), expected);
+        assert!(market_report("", 2).is_err());
+    }
+
+    #[test]
+    fn test_parse_sequence() {
+        assert_eq!(parse_sequence("-2,1,-1,3").unwrap(), [-2, 1, -1, 3]);
+        assert!(parse_sequence("-2,1,-1").is_err());
+        assert!(parse_sequence("-2,1,-1,10").is_err());
+        assert!(parse_sequence("a,b,c,d").is_err());
+    }
+
     #[test]
     fn test_with_input() {
         let input = read_file_from_path("docs/challenge_2.txt"); // Load sample input for Part 2
         assert_eq!(solve(&input).unwrap(), 2058); // Verify the result for Part 2
     }
 }
-
-fn main() {
-    let input = &read_file_from_args(); // Include the input data as a string
-    println!("result: {}", solve(input).unwrap()); // Compute and print Part 2 result
-}
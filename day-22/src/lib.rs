@@ -0,0 +1,339 @@
+#![cfg_attr(feature = "simd", feature(portable_simd))]
+
+#[cfg(feature = "simd")]
+use std::simd::Simd;
+
+/// Secrets are pruned to their low 24 bits (modulo 16777216) after every step.
+pub const PRUNE_MASK: u32 = 16_777_216 - 1;
+
+/// Number of buyers advanced together by a [`SecretBatch`].
+pub const LANES: usize = 8;
+
+/// Number of distinct packed windows of four price changes (each change is in -9..=9).
+pub const WINDOW_KEYS: usize = 19 * 19 * 19 * 19;
+
+/// Computes the next secret number: multiply, divide and multiply again, mixing and
+/// pruning after each stage.
+pub const fn next_secret(mut secret: u32) -> u32 {
+    secret = ((secret << 6) ^ secret) & PRUNE_MASK; // Step 1: Multiply by 64, XOR, and prune
+    secret = ((secret >> 5) ^ secret) & PRUNE_MASK; // Step 2: Divide by 32, XOR, and prune
+    ((secret << 11) ^ secret) & PRUNE_MASK // Step 3: Multiply by 2048, XOR, and prune
+}
+
+/// Pseudo-random generator yielding the successive secret numbers of one buyer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SecretRng {
+    secret: u32,
+}
+
+impl SecretRng {
+    /// Creates a generator starting from the buyer's initial secret.
+    pub fn new(seed: u32) -> Self {
+        SecretRng { secret: seed }
+    }
+
+    /// Returns the most recently generated secret (the seed before the first step).
+    pub fn secret(&self) -> u32 {
+        self.secret
+    }
+
+    /// Returns the prices (ones digits) starting with the seed's own price.
+    pub fn prices(self) -> impl Iterator<Item = u8> {
+        std::iter::once(self.secret)
+            .chain(self)
+            .map(|secret| (secret % 10) as u8)
+    }
+}
+
+impl Iterator for SecretRng {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+        self.secret = next_secret(self.secret);
+        Some(self.secret)
+    }
+}
+
+/// Secrets of up to [`LANES`] buyers evolved in lockstep. Unused lanes hold zero, which
+/// is a fixed point of the generator.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SecretBatch {
+    lanes: [u32; LANES],
+}
+
+impl SecretBatch {
+    /// Loads up to [`LANES`] seeds into a batch.
+    pub fn new(seeds: &[u32]) -> Self {
+        assert!(
+            seeds.len() <= LANES,
+            "a batch holds at most {} seeds",
+            LANES
+        );
+        let mut lanes = [0; LANES];
+        lanes[..seeds.len()].copy_from_slice(seeds);
+        SecretBatch { lanes }
+    }
+
+    /// Returns the current secret of every lane.
+    pub fn secrets(&self) -> &[u32; LANES] {
+        &self.lanes
+    }
+
+    /// Advances every lane by one step using portable SIMD vectors.
+    #[cfg(feature = "simd")]
+    pub fn advance(&mut self) {
+        let mask = Simd::splat(PRUNE_MASK);
+        let mut s = Simd::<u32, LANES>::from_array(self.lanes);
+        s = ((s << 6) ^ s) & mask;
+        s = ((s >> 5) ^ s) & mask;
+        s = ((s << 11) ^ s) & mask;
+        self.lanes = s.to_array();
+    }
+
+    /// Advances every lane by one step. The fixed-width lane loop is shaped so the
+    /// compiler can vectorise it on stable toolchains.
+    #[cfg(not(feature = "simd"))]
+    pub fn advance(&mut self) {
+        for lane in self.lanes.iter_mut() {
+            *lane = next_secret(*lane);
+        }
+    }
+
+    /// Advances every lane by `steps` steps.
+    pub fn advance_by(&mut self, steps: usize) {
+        for _ in 0..steps {
+            self.advance();
+        }
+    }
+}
+
+/// Computes the secret reached by each seed after `steps` steps, [`LANES`] buyers at a time.
+pub fn nth_secrets(seeds: &[u32], steps: usize) -> Vec<u32> {
+    seeds
+        .chunks(LANES)
+        .flat_map(|chunk| {
+            let mut batch = SecretBatch::new(chunk);
+            batch.advance_by(steps);
+            batch.secrets()[..chunk.len()].to_vec()
+        })
+        .collect()
+}
+
+/// Packs four price changes into a single index below [`WINDOW_KEYS`] (base 19, oldest first).
+pub fn encode_window(changes: [i8; 4]) -> usize {
+    changes
+        .iter()
+        .fold(0, |key, &change| key * 19 + (change + 9) as usize)
+}
+
+/// Unpacks an index produced by [`encode_window`] back into the four price changes.
+pub fn decode_window(mut key: usize) -> [i8; 4] {
+    let mut changes = [0; 4];
+    for change in changes.iter_mut().rev() {
+        *change = (key % 19) as i8 - 9;
+        key /= 19;
+    }
+    changes
+}
+
+/// Sums, for every packed window of four price changes, the price each buyer sells at the
+/// first time that window appears within `steps` steps. Windows never seen stay at zero.
+pub fn window_totals(seeds: &[u32], steps: usize) -> Vec<u32> {
+    let mut totals = vec![0u32; WINDOW_KEYS];
+    // Lanes that already sold on each window, tagged with the (1-based) batch they belong
+    // to so the arrays never need clearing between batches
+    let mut seen_batch = vec![0u32; WINDOW_KEYS];
+    let mut seen_lanes = vec![0u8; WINDOW_KEYS];
+
+    for (chunk_index, chunk) in seeds.chunks(LANES).enumerate() {
+        let batch_tag = chunk_index as u32 + 1;
+        let mut batch = SecretBatch::new(chunk);
+        let mut prices = batch.secrets().map(|secret| (secret % 10) as u8);
+        let mut keys = [0usize; LANES];
+
+        for step in 1..=steps {
+            batch.advance();
+            for (lane, &secret) in batch.secrets()[..chunk.len()].iter().enumerate() {
+                let price = (secret % 10) as u8;
+                let change = price + 9 - prices[lane];
+                let key = (keys[lane] * 19 + change as usize) % WINDOW_KEYS;
+                keys[lane] = key;
+                prices[lane] = price;
+
+                if step < 4 {
+                    continue;
+                }
+                if seen_batch[key] != batch_tag {
+                    seen_batch[key] = batch_tag;
+                    seen_lanes[key] = 0;
+                }
+                if seen_lanes[key] & (1 << lane) == 0 {
+                    seen_lanes[key] |= 1 << lane;
+                    totals[key] += price as u32;
+                }
+            }
+        }
+    }
+
+    totals
+}
+
+/// Returns the `n` windows with the highest totals as (changes, bananas), best first.
+/// Ties are broken by the packed key so the ranking is deterministic.
+pub fn top_windows(totals: &[u32], n: usize) -> Vec<([i8; 4], u32)> {
+    let mut ranked = totals
+        .iter()
+        .enumerate()
+        .filter(|&(_, &total)| total > 0)
+        .collect::<Vec<_>>();
+    ranked.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(&b.0)));
+
+    ranked
+        .into_iter()
+        .take(n)
+        .map(|(key, &total)| (decode_window(key), total))
+        .collect()
+}
+
+/// Finds when a buyer first sees `changes` within `steps` steps, returning the step at
+/// which the monkey sells and the price it gets, or `None` if the window never appears.
+pub fn first_sale(seed: u32, changes: [i8; 4], steps: usize) -> Option<(usize, u8)> {
+    let target = encode_window(changes);
+    let mut key = 0;
+
+    SecretRng::new(seed)
+        .prices()
+        .take(steps + 1)
+        .collect::<Vec<_>>()
+        .windows(2)
+        .enumerate()
+        .find_map(|(index, pair)| {
+            key = (key * 19 + (pair[1] + 9 - pair[0]) as usize) % WINDOW_KEYS;
+            (index >= 3 && key == target).then_some((index + 1, pair[1]))
+        })
+}
+
+/// Exports every buyer's price series as CSV, one row per buyer and step. The change
+/// column is empty for step 0, which only has the seed's own price.
+pub fn prices_csv(seeds: &[u32], steps: usize) -> String {
+    let mut csv = String::from("buyer,seed,step,secret,price,change\n");
+
+    for (buyer, &seed) in seeds.iter().enumerate() {
+        let mut previous = None;
+        let secrets = std::iter::once(seed)
+            .chain(SecretRng::new(seed))
+            .take(steps + 1);
+
+        for (step, secret) in secrets.enumerate() {
+            let price = (secret % 10) as i8;
+            let change = previous.map_or(String::new(), |prev: i8| (price - prev).to_string());
+            csv.push_str(&format!(
+                "{},{},{},{},{},{}\n",
+                buyer + 1,
+                seed,
+                step,
+                secret,
+                price,
+                change
+            ));
+            previous = Some(price);
+        }
+    }
+
+    csv
+}
+
+/// Parses one initial secret per line, ignoring lines that are not numbers.
+pub fn parse_seeds(input: &str) -> Vec<u32> {
+    input
+        .lines()
+        .filter_map(|line| line.trim().parse().ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_secret_rng() {
+        let secrets = SecretRng::new(123).take(10).collect::<Vec<_>>();
+        assert_eq!(
+            secrets,
+            vec![
+                15887950, 16495136, 527345, 704524, 1553684, 12683156, 11100544, 12249484, 7753432,
+                5908254
+            ]
+        );
+
+        let prices = SecretRng::new(123).prices().take(10).collect::<Vec<_>>();
+        assert_eq!(prices, vec![3, 0, 6, 5, 4, 4, 6, 4, 4, 2]);
+    }
+
+    #[test]
+    fn test_nth_secrets_matches_scalar() {
+        // More seeds than lanes, so the last batch is only partly filled
+        let seeds = (1..=LANES as u32 + 3).map(|i| i * 977).collect::<Vec<_>>();
+        let expected = seeds
+            .iter()
+            .map(|&seed| SecretRng::new(seed).nth(1999).unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(nth_secrets(&seeds, 2000), expected);
+        assert_eq!(
+            nth_secrets(&[1, 10, 100, 2024], 2000),
+            vec![8685429, 4700978, 15273692, 8667524]
+        );
+    }
+
+    #[test]
+    fn test_window_encoding() {
+        let changes = [-2, 1, -1, 3];
+        assert_eq!(decode_window(encode_window(changes)), changes);
+        assert_eq!(encode_window([-9, -9, -9, -9]), 0);
+        assert_eq!(encode_window([9, 9, 9, 9]), WINDOW_KEYS - 1);
+    }
+
+    #[test]
+    fn test_report_helpers() {
+        let totals = window_totals(&[1, 2, 3, 2024], 2000);
+        assert_eq!(
+            top_windows(&totals, 3),
+            vec![
+                ([-2, 1, -1, 3], 23),
+                ([-1, 3, 1, 0], 22),
+                ([1, -3, 5, 1], 21)
+            ]
+        );
+
+        assert_eq!(first_sale(1, [-2, 1, -1, 3], 2000), Some((1964, 7)));
+        assert_eq!(first_sale(2, [-2, 1, -1, 3], 2000), Some((291, 7)));
+        assert_eq!(first_sale(3, [-2, 1, -1, 3], 2000), None);
+        assert_eq!(first_sale(2024, [-2, 1, -1, 3], 2000), Some((455, 9)));
+
+        let csv = prices_csv(&[123], 4);
+        let rows = csv.lines().collect::<Vec<_>>();
+        assert_eq!(rows[0], "buyer,seed,step,secret,price,change");
+        assert_eq!(rows[1], "1,123,0,123,3,");
+        assert_eq!(rows[2], "1,123,1,15887950,0,-3");
+        assert_eq!(rows.len(), 6);
+    }
+
+    #[test]
+    fn test_window_totals() {
+        let totals = window_totals(&[1, 2, 3, 2024], 2000);
+        assert_eq!(totals[encode_window([-2, 1, -1, 3])], 23);
+        assert_eq!(totals.iter().max(), Some(&23));
+
+        // Buyers sharing a batch must each count their own first sale
+        let seeds = (1..=LANES as u32 + 3).collect::<Vec<_>>();
+        let combined = window_totals(&seeds, 200);
+        let mut separate = vec![0; WINDOW_KEYS];
+        for &seed in &seeds {
+            for (key, total) in window_totals(&[seed], 200).into_iter().enumerate() {
+                separate[key] += total;
+            }
+        }
+        assert_eq!(combined, separate);
+    }
+}
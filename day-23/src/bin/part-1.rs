@@ -1,10 +1,13 @@
-use std::collections::{HashMap, HashSet, VecDeque};
+use day_23::NetworkGraph;
 
 /// Reads the input file from command-line arguments
 pub fn read_file_from_args() -> String {
     let args: Vec<String> = std::env::args().collect();
     if args.len() < 2 {
-        eprintln!("Usage: {} <input_file>", args[0]);
+        eprintln!(
+            "Usage: {} <input_file> [clique_size] [name_prefix]",
+            args[0]
+        );
         std::process::exit(1);
     }
     std::fs::read_to_string(&args[1]).expect("Failed to read input file")
@@ -15,64 +18,37 @@ pub fn read_file_from_path(file_path: &str) -> String {
     std::fs::read_to_string(file_path).expect("Failed to read input file")
 }
 
-/// Builds the adjacency map and identifies all computers whose names start with 't'
-fn build_adj_map(input: &str) -> (HashMap<&str, HashSet<&str>>, HashSet<&str>) {
-    let mut map = HashMap::<&str, HashSet<&str>>::new();
-    let mut t_computers = HashSet::new();
+/// Counts the cliques of `size` computers where at least one name starts with `prefix`
+pub fn count_cliques_with_prefix(input: &str, size: usize, prefix: &str) -> usize {
+    let graph = NetworkGraph::parse(input);
 
-    // Parse connections and build adjacency map
-    for line in input.lines() {
-        if let Some((a, b)) = line.split_once('-') {
-            // Add both directions to the adjacency map
-            map.entry(a).or_default().insert(b);
-            map.entry(b).or_default().insert(a);
-
-            // Track computers with names starting with 't'
-            if a.starts_with('t') {
-                t_computers.insert(a);
-            }
-            if b.starts_with('t') {
-                t_computers.insert(b);
-            }
-        }
-    }
-
-    (map, t_computers)
+    graph.count_k_cliques(size, |names| {
+        names.iter().any(|name| name.starts_with(prefix))
+    })
 }
 
 /// Finds the count of all triangles (fully connected sets of 3 nodes)
 /// where at least one computer's name starts with 't'
 pub fn solve(input: &str) -> usize {
-    let (map, t_computers) = build_adj_map(input);
-
-    // To track unique triangles (sorted to avoid duplicate representations)
-    let mut unique_triangles = HashSet::new();
+    count_cliques_with_prefix(input, 3, "t")
+}
 
-    // Start BFS for all 't' computers
-    let mut bfs_queue: VecDeque<_> = t_computers
-        .into_iter()
-        .map(|node| (node, vec![node]))
-        .collect();
+fn main() {
+    let input = read_file_from_args();
 
-    while let Some((current, mut path)) = bfs_queue.pop_front() {
-        if path.len() == 3 {
-            // Ensure the triangle is added in a unique sorted order
-            path.sort_unstable();
-            unique_triangles.insert(path);
-            continue;
-        }
-        for &neighbor in map[&current].iter() {
-            // Check if neighbor forms a triangle with the starting node
-            if !path.contains(&neighbor) && map[&neighbor].contains(&path[0]) {
-                let mut new_path = path.clone();
-                new_path.push(neighbor);
-                bfs_queue.push_back((neighbor, new_path));
-            }
-        }
-    }
+    // Optional clique size and name prefix, defaulting to the puzzle's triangles with a `t*` computer
+    let args: Vec<String> = std::env::args().collect();
+    let size = args
+        .get(2)
+        .map_or(3, |arg| arg.parse().expect("Invalid clique size"));
+    let prefix = args.get(3).map_or("t", String::as_str);
 
-    unique_triangles.len()
+    println!(
+        "result: {}",
+        count_cliques_with_prefix(&input, size, prefix)
+    );
 }
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -113,6 +89,8 @@ tb-vc
 td-yn"#;
 
         assert_eq!(solve(EXAMPLE), 7);
+        assert_eq!(count_cliques_with_prefix(EXAMPLE, 4, "t"), 1);
+        assert_eq!(count_cliques_with_prefix(EXAMPLE, 3, "w"), 6);
     }
 
     #[test]
@@ -121,8 +99,3 @@ td-yn"#;
         assert_eq!(solve(&input), 1419);
     }
 }
-
-fn main() {
-    let input = read_file_from_args();
-    println!("result: {}", solve(&input));
-}
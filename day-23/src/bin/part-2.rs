@@ -1,5 +1,4 @@
-use std::collections::{HashMap, HashSet};
-use std::iter::once;
+use day_23::NetworkGraph;
 
 /// Reads the input file from command-line arguments
 pub fn read_file_from_args() -> String {
@@ -18,76 +17,15 @@ pub fn read_file_from_path(file_path: &str) -> String {
 
 /// Finds the largest fully connected set of computers and returns the password
 pub fn solve(input: &str) -> String {
-    let (map, _) = build_adj_map(input);
+    let graph = NetworkGraph::parse(input);
 
-    // To store the largest fully connected set of nodes
-    let mut largest_clique = HashSet::new();
-
-    find_largest_clique(
-        HashSet::new(),
-        map.keys().copied().collect(),
-        HashSet::new(),
-        &map,
-        &mut largest_clique,
-    );
-
-    // Convert the largest clique to a sorted, comma-separated password
-    let mut pwds: Vec<_> = largest_clique.into_iter().collect();
-    pwds.sort_unstable();
-    pwds.join(",")
-}
-
-/// Recursive function to find the largest fully connected set of nodes
-fn find_largest_clique<'a>(
-    current_clique: HashSet<&'a str>,
-    mut candidates: HashSet<&'a str>,
-    mut excluded: HashSet<&'a str>,
-    map: &HashMap<&str, HashSet<&'a str>>,
-    largest_clique: &mut HashSet<&'a str>,
-) {
-    if candidates.is_empty() && excluded.is_empty() {
-        if current_clique.len() > largest_clique.len() {
-            *largest_clique = current_clique;
-        }
-        return;
-    }
-
-    for &node in candidates.clone().iter() {
-        let neighbors = &map[node];
-        find_largest_clique(
-            current_clique.iter().copied().chain(once(node)).collect(),
-            candidates.intersection(neighbors).copied().collect(),
-            excluded.intersection(neighbors).copied().collect(),
-            map,
-            largest_clique,
-        );
-        candidates.remove(node);
-        excluded.insert(node);
-    }
+    // The password is the sorted, comma-separated list of the largest clique's members
+    graph.names_of(&graph.maximum_clique()).join(",")
 }
 
-/// Builds the adjacency map from input and identifies all computers whose names start with 't'
-fn build_adj_map(input: &str) -> (HashMap<&str, HashSet<&str>>, HashSet<&str>) {
-    let mut map = HashMap::<&str, HashSet<&str>>::new();
-    let mut t_computers = HashSet::new();
-
-    // Parse and build adjacency map
-    for line in input.lines() {
-        if let Some((a, b)) = line.split_once('-') {
-            map.entry(a).or_default().insert(b);
-            map.entry(b).or_default().insert(a);
-
-            // Track computers with names starting with 't'
-            if a.starts_with('t') {
-                t_computers.insert(a);
-            }
-            if b.starts_with('t') {
-                t_computers.insert(b);
-            }
-        }
-    }
-
-    (map, t_computers)
+fn main() {
+    let input = read_file_from_args();
+    println!("result: {}", solve(&input));
 }
 
 #[cfg(test)]
@@ -138,8 +76,3 @@ td-yn"#;
         assert_eq!(solve(&input), "af,aq,ck,ee,fb,it,kg,of,ol,rt,sc,vk,zh");
     }
 }
-
-fn main() {
-    let input = read_file_from_args();
-    println!("result: {}", solve(&input));
-}
@@ -0,0 +1,477 @@
+use std::collections::HashMap;
+
+/// Interned identifier of a computer in a [`NetworkGraph`].
+pub type NodeId = usize;
+
+/// Fixed-size bit set over node IDs, used for fast neighbourhood intersections.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NodeSet {
+    words: Vec<u64>,
+}
+
+impl NodeSet {
+    /// Creates an empty set able to hold IDs below `capacity`.
+    pub fn new(capacity: usize) -> Self {
+        NodeSet {
+            words: vec![0; capacity.div_ceil(64)],
+        }
+    }
+
+    /// Adds a node to the set.
+    pub fn insert(&mut self, id: NodeId) {
+        self.words[id / 64] |= 1 << (id % 64);
+    }
+
+    /// Removes a node from the set.
+    pub fn remove(&mut self, id: NodeId) {
+        self.words[id / 64] &= !(1 << (id % 64));
+    }
+
+    /// Checks whether a node is in the set.
+    pub fn contains(&self, id: NodeId) -> bool {
+        self.words[id / 64] & (1 << (id % 64)) != 0
+    }
+
+    /// Returns the number of nodes in the set.
+    pub fn len(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    /// Checks whether the set is empty.
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&word| word == 0)
+    }
+
+    /// Returns the nodes present in both sets.
+    pub fn intersection(&self, other: &NodeSet) -> NodeSet {
+        NodeSet {
+            words: self
+                .words
+                .iter()
+                .zip(&other.words)
+                .map(|(a, b)| a & b)
+                .collect(),
+        }
+    }
+
+    /// Returns the nodes present in this set but not in `other`.
+    pub fn difference(&self, other: &NodeSet) -> NodeSet {
+        NodeSet {
+            words: self
+                .words
+                .iter()
+                .zip(&other.words)
+                .map(|(a, b)| a & !b)
+                .collect(),
+        }
+    }
+
+    /// Returns the nodes present in either set.
+    pub fn union(&self, other: &NodeSet) -> NodeSet {
+        NodeSet {
+            words: self
+                .words
+                .iter()
+                .zip(&other.words)
+                .map(|(a, b)| a | b)
+                .collect(),
+        }
+    }
+
+    /// Counts the nodes present in both sets without allocating.
+    pub fn intersection_len(&self, other: &NodeSet) -> usize {
+        self.words
+            .iter()
+            .zip(&other.words)
+            .map(|(a, b)| (a & b).count_ones() as usize)
+            .sum()
+    }
+
+    /// Iterates over the node IDs in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = NodeId> + '_ {
+        self.words.iter().enumerate().flat_map(|(i, &word)| {
+            let mut bits = word;
+            std::iter::from_fn(move || {
+                if bits == 0 {
+                    return None;
+                }
+                let bit = bits.trailing_zeros() as usize;
+                bits &= bits - 1;
+                Some(i * 64 + bit)
+            })
+        })
+    }
+}
+
+/// Undirected graph of computers with interned node IDs.
+#[derive(Clone, Debug, Default)]
+pub struct NetworkGraph {
+    names: Vec<String>,           // node name by ID
+    ids: HashMap<String, NodeId>, // node ID by name
+    neighbors: Vec<Vec<NodeId>>,  // sorted adjacency lists
+}
+
+impl NetworkGraph {
+    /// Builds the graph from `a-b` connection lines, ignoring lines without a `-`.
+    pub fn parse(input: &str) -> Self {
+        let mut graph = NetworkGraph::default();
+        for line in input.lines() {
+            if let Some((a, b)) = line.trim().split_once('-') {
+                graph.add_edge(a, b);
+            }
+        }
+        graph
+    }
+
+    /// Returns the ID of a node, interning the name if it has not been seen yet.
+    pub fn intern(&mut self, name: &str) -> NodeId {
+        if let Some(&id) = self.ids.get(name) {
+            return id;
+        }
+        let id = self.names.len();
+        self.names.push(name.to_string());
+        self.ids.insert(name.to_string(), id);
+        self.neighbors.push(Vec::new());
+        id
+    }
+
+    /// Adds an undirected connection between two computers.
+    pub fn add_edge(&mut self, a: &str, b: &str) {
+        let (a, b) = (self.intern(a), self.intern(b));
+        if a == b {
+            return;
+        }
+        for (from, to) in [(a, b), (b, a)] {
+            if let Err(pos) = self.neighbors[from].binary_search(&to) {
+                self.neighbors[from].insert(pos, to);
+            }
+        }
+    }
+
+    /// Returns the number of computers in the graph.
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    /// Checks whether the graph has no computers.
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Looks up the ID of a computer by name.
+    pub fn id(&self, name: &str) -> Option<NodeId> {
+        self.ids.get(name).copied()
+    }
+
+    /// Returns the name of a computer.
+    pub fn name(&self, id: NodeId) -> &str {
+        &self.names[id]
+    }
+
+    /// Returns the sorted neighbours of a computer.
+    pub fn neighbors(&self, id: NodeId) -> &[NodeId] {
+        &self.neighbors[id]
+    }
+
+    /// Checks whether two computers are directly connected.
+    pub fn are_connected(&self, a: NodeId, b: NodeId) -> bool {
+        self.neighbors[a].binary_search(&b).is_ok()
+    }
+
+    /// Returns the sorted names of a set of nodes.
+    pub fn names_of(&self, nodes: &[NodeId]) -> Vec<&str> {
+        let mut names = nodes.iter().map(|&id| self.name(id)).collect::<Vec<_>>();
+        names.sort_unstable();
+        names
+    }
+
+    /// Computes a degeneracy ordering by repeatedly removing a node of minimum remaining
+    /// degree. Returns the ordering and the degeneracy (largest degree seen at removal).
+    pub fn degeneracy_ordering(&self) -> (Vec<NodeId>, usize) {
+        let n = self.len();
+        let max_degree = self.neighbors.iter().map(Vec::len).max().unwrap_or(0);
+        let mut degree = self.neighbors.iter().map(Vec::len).collect::<Vec<_>>();
+        let mut buckets = vec![Vec::new(); max_degree + 1];
+        for (id, &d) in degree.iter().enumerate() {
+            buckets[d].push(id);
+        }
+
+        let mut removed = vec![false; n];
+        let mut order = Vec::with_capacity(n);
+        let mut degeneracy = 0;
+        let mut lowest = 0;
+
+        while order.len() < n {
+            // Buckets may hold stale entries, so skip nodes whose degree has since dropped
+            let Some(id) = buckets[lowest].pop() else {
+                lowest += 1;
+                continue;
+            };
+            if removed[id] || degree[id] != lowest {
+                continue;
+            }
+
+            removed[id] = true;
+            degeneracy = degeneracy.max(lowest);
+            order.push(id);
+
+            for &neighbor in &self.neighbors[id] {
+                if !removed[neighbor] {
+                    degree[neighbor] -= 1;
+                    buckets[degree[neighbor]].push(neighbor);
+                }
+            }
+            lowest = lowest.saturating_sub(1);
+        }
+
+        (order, degeneracy)
+    }
+
+    /// Returns the neighbourhood of every node as a bit set.
+    fn neighbor_sets(&self) -> Vec<NodeSet> {
+        self.neighbors
+            .iter()
+            .map(|list| {
+                let mut set = NodeSet::new(self.len());
+                list.iter().for_each(|&id| set.insert(id));
+                set
+            })
+            .collect()
+    }
+
+    /// Lists every maximal clique using Bron–Kerbosch with pivoting, started from each
+    /// node in degeneracy order so the recursion depth stays bounded by the degeneracy.
+    pub fn maximal_cliques(&self) -> Vec<Vec<NodeId>> {
+        let sets = self.neighbor_sets();
+        let (order, _) = self.degeneracy_ordering();
+        let mut position = vec![0; self.len()];
+        for (i, &id) in order.iter().enumerate() {
+            position[id] = i;
+        }
+
+        let mut cliques = Vec::new();
+        for &id in &order {
+            // Later neighbours are candidates, earlier ones were already explored
+            let mut candidates = NodeSet::new(self.len());
+            let mut excluded = NodeSet::new(self.len());
+            for &neighbor in &self.neighbors[id] {
+                if position[neighbor] > position[id] {
+                    candidates.insert(neighbor);
+                } else {
+                    excluded.insert(neighbor);
+                }
+            }
+            bron_kerbosch(&sets, &mut vec![id], candidates, excluded, &mut cliques);
+        }
+        cliques
+    }
+
+    /// Returns one largest clique, or an empty list for an empty graph.
+    pub fn maximum_clique(&self) -> Vec<NodeId> {
+        self.maximal_cliques()
+            .into_iter()
+            .max_by_key(Vec::len)
+            .unwrap_or_default()
+    }
+
+    /// Lists every clique of exactly `k` nodes whose member names satisfy `predicate`.
+    /// Each clique is reported once, ordered along the degeneracy ordering.
+    pub fn k_cliques<F>(&self, k: usize, predicate: F) -> Vec<Vec<NodeId>>
+    where
+        F: Fn(&[&str]) -> bool,
+    {
+        let mut cliques = Vec::new();
+        if k == 0 {
+            return cliques;
+        }
+
+        let sets = self.neighbor_sets();
+        let (order, _) = self.degeneracy_ordering();
+        let mut later = NodeSet::new(self.len());
+        for &id in &order {
+            later.insert(id);
+        }
+
+        for &id in &order {
+            later.remove(id);
+            let candidates = sets[id].intersection(&later);
+            extend_k_cliques(&sets, k, &mut vec![id], candidates, &mut |clique| {
+                let names = clique.iter().map(|&id| self.name(id)).collect::<Vec<_>>();
+                if predicate(&names) {
+                    cliques.push(clique.to_vec());
+                }
+            });
+        }
+        cliques
+    }
+
+    /// Counts the cliques of exactly `k` nodes whose member names satisfy `predicate`.
+    pub fn count_k_cliques<F>(&self, k: usize, predicate: F) -> usize
+    where
+        F: Fn(&[&str]) -> bool,
+    {
+        self.k_cliques(k, predicate).len()
+    }
+}
+
+/// Bron–Kerbosch recursion with Tomita pivoting: only candidates outside the pivot's
+/// neighbourhood are branched on, since any maximal clique must include one of them.
+fn bron_kerbosch(
+    sets: &[NodeSet],
+    clique: &mut Vec<NodeId>,
+    mut candidates: NodeSet,
+    mut excluded: NodeSet,
+    cliques: &mut Vec<Vec<NodeId>>,
+) {
+    if candidates.is_empty() {
+        if excluded.is_empty() {
+            cliques.push(clique.clone());
+        }
+        return;
+    }
+
+    // Pick the pivot that leaves the fewest branches
+    let pivot = candidates
+        .union(&excluded)
+        .iter()
+        .max_by_key(|&u| candidates.intersection_len(&sets[u]))
+        .unwrap();
+
+    for node in candidates
+        .difference(&sets[pivot])
+        .iter()
+        .collect::<Vec<_>>()
+    {
+        clique.push(node);
+        bron_kerbosch(
+            sets,
+            clique,
+            candidates.intersection(&sets[node]),
+            excluded.intersection(&sets[node]),
+            cliques,
+        );
+        clique.pop();
+        candidates.remove(node);
+        excluded.insert(node);
+    }
+}
+
+/// Grows `clique` with candidates until it has `k` members, reporting each complete clique.
+fn extend_k_cliques(
+    sets: &[NodeSet],
+    k: usize,
+    clique: &mut Vec<NodeId>,
+    candidates: NodeSet,
+    report: &mut dyn FnMut(&[NodeId]),
+) {
+    if clique.len() == k {
+        report(clique);
+        return;
+    }
+    if clique.len() + candidates.len() < k {
+        return;
+    }
+
+    let mut remaining = candidates;
+    for node in remaining.clone().iter() {
+        remaining.remove(node);
+        clique.push(node);
+        extend_k_cliques(sets, k, clique, remaining.intersection(&sets[node]), report);
+        clique.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = r#"kh-tc
+qp-kh
+de-cg
+ka-co
+yn-aq
+qp-ub
+cg-tb
+vc-aq
+tb-ka
+wh-tc
+yn-cg
+kh-ub
+ta-co
+de-co
+tc-td
+tb-wq
+wh-td
+ta-ka
+td-qp
+aq-cg
+wq-ub
+ub-vc
+de-ta
+wq-aq
+wq-vc
+wh-yn
+ka-de
+kh-ta
+co-tc
+wh-qp
+tb-vc
+td-yn"#;
+
+    #[test]
+    fn test_k_cliques() {
+        let graph = NetworkGraph::parse(EXAMPLE);
+        let has_t = |names: &[&str]| names.iter().any(|name| name.starts_with('t'));
+
+        assert_eq!(graph.count_k_cliques(3, |_| true), 12);
+        assert_eq!(graph.count_k_cliques(3, has_t), 7);
+        assert_eq!(graph.count_k_cliques(4, |_| true), 1);
+        assert_eq!(graph.count_k_cliques(5, |_| true), 0);
+
+        let quads = graph.k_cliques(4, has_t);
+        assert_eq!(graph.names_of(&quads[0]), vec!["co", "de", "ka", "ta"]);
+    }
+
+    #[test]
+    fn test_maximal_cliques() {
+        let graph = NetworkGraph::parse(EXAMPLE);
+        let mut cliques = graph
+            .maximal_cliques()
+            .iter()
+            .map(|clique| graph.names_of(clique).join(","))
+            .collect::<Vec<_>>();
+        cliques.sort();
+
+        // Eight triangles, the co,de,ka,ta quad and six edges outside any triangle
+        assert_eq!(cliques.len(), 15);
+        assert!(cliques.contains(&"co,de,ka,ta".to_string()));
+        assert!(cliques.contains(&"aq,cg,yn".to_string()));
+        assert!(cliques.contains(&"cg,de".to_string()));
+        assert_eq!(
+            graph.names_of(&graph.maximum_clique()),
+            vec!["co", "de", "ka", "ta"]
+        );
+    }
+
+    #[test]
+    fn test_degeneracy_ordering() {
+        // A triangle with a pendant node has degeneracy 2
+        let graph = NetworkGraph::parse("a-b\nb-c\nc-a\nc-d");
+        let (order, degeneracy) = graph.degeneracy_ordering();
+
+        assert_eq!(degeneracy, 2);
+        assert_eq!(order.len(), 4);
+        assert_eq!(graph.name(order[0]), "d");
+    }
+
+    #[test]
+    fn test_with_empty_graph() {
+        let graph = NetworkGraph::parse("");
+        assert!(graph.is_empty());
+        assert!(graph.maximum_clique().is_empty());
+        assert_eq!(graph.count_k_cliques(3, |_| true), 0);
+    }
+}
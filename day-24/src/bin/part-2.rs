@@ -12,7 +12,7 @@ enum GateLogic {
 pub fn read_file_from_args() -> String {
     let args: Vec<String> = std::env::args().collect();
     if args.len() < 2 {
-        eprintln!("Usage: {} <input_file>", args[0]);
+        eprintln!("Usage: {} <input_file> [--dot|--verilog]", args[0]);
         std::process::exit(1);
     }
     std::fs::read_to_string(&args[1]).expect("Failed to read input file")
@@ -23,13 +23,198 @@ pub fn read_file_by_path(file_path: &str) -> String {
     std::fs::read_to_string(file_path).expect("Failed to read input file")
 }
 
-/// Solves the problem by analyzing the system of gates and wire connections.
-fn solve(input: &str) -> String {
+/// A gate as parsed from the input: two input wires, the output wire and its logic.
+type Gate<'a> = (&'a str, &'a str, &'a str, GateLogic);
+
+/// Output formats supported by the command line, selected by the optional second argument.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum OutputMode {
+    Result,  // comma-separated list of swapped wires
+    Dot,     // Graphviz DOT netlist
+    Verilog, // structural Verilog module
+}
+
+impl GateLogic {
+    /// Returns the keyword used for this gate in the puzzle input.
+    fn name(self) -> &'static str {
+        match self {
+            GateLogic::And => "AND",
+            GateLogic::Or => "OR",
+            GateLogic::Xor => "XOR",
+        }
+    }
+
+    /// Returns the fill colour used for this gate in the DOT export.
+    fn color(self) -> &'static str {
+        match self {
+            GateLogic::And => "lightblue",
+            GateLogic::Or => "palegreen",
+            GateLogic::Xor => "gold",
+        }
+    }
+
+    /// Returns the Verilog gate primitive matching this gate.
+    fn primitive(self) -> &'static str {
+        match self {
+            GateLogic::And => "and",
+            GateLogic::Or => "or",
+            GateLogic::Xor => "xor",
+        }
+    }
+}
+
+/// Reserved words of IEEE 1364-2005 Verilog, sorted. Wire names matching one of them must be
+/// escaped before they can be used as identifiers.
+const VERILOG_KEYWORDS: &[&str] = &[
+    "always",
+    "and",
+    "assign",
+    "automatic",
+    "begin",
+    "buf",
+    "bufif0",
+    "bufif1",
+    "case",
+    "casex",
+    "casez",
+    "cell",
+    "cmos",
+    "config",
+    "deassign",
+    "default",
+    "defparam",
+    "design",
+    "disable",
+    "edge",
+    "else",
+    "end",
+    "endcase",
+    "endconfig",
+    "endfunction",
+    "endgenerate",
+    "endmodule",
+    "endprimitive",
+    "endspecify",
+    "endtable",
+    "endtask",
+    "event",
+    "for",
+    "force",
+    "forever",
+    "fork",
+    "function",
+    "generate",
+    "genvar",
+    "highz0",
+    "highz1",
+    "if",
+    "ifnone",
+    "incdir",
+    "include",
+    "initial",
+    "inout",
+    "input",
+    "instance",
+    "integer",
+    "join",
+    "large",
+    "liblist",
+    "library",
+    "localparam",
+    "macromodule",
+    "medium",
+    "module",
+    "nand",
+    "negedge",
+    "nmos",
+    "nor",
+    "noshowcancelled",
+    "not",
+    "notif0",
+    "notif1",
+    "or",
+    "output",
+    "parameter",
+    "pmos",
+    "posedge",
+    "primitive",
+    "pull0",
+    "pull1",
+    "pulldown",
+    "pullup",
+    "pulsestyle_ondetect",
+    "pulsestyle_onevent",
+    "rcmos",
+    "real",
+    "realtime",
+    "reg",
+    "release",
+    "repeat",
+    "rnmos",
+    "rpmos",
+    "rtran",
+    "rtranif0",
+    "rtranif1",
+    "scalared",
+    "showcancelled",
+    "signed",
+    "small",
+    "specify",
+    "specparam",
+    "strong0",
+    "strong1",
+    "supply0",
+    "supply1",
+    "table",
+    "task",
+    "time",
+    "tran",
+    "tranif0",
+    "tranif1",
+    "tri",
+    "tri0",
+    "tri1",
+    "triand",
+    "trior",
+    "trireg",
+    "unsigned",
+    "use",
+    "uwire",
+    "vectored",
+    "wait",
+    "wand",
+    "weak0",
+    "weak1",
+    "while",
+    "wire",
+    "wor",
+    "xnor",
+    "xor",
+];
+
+/// Reads the optional output mode (`--dot` or `--verilog`) that follows the input file.
+fn read_mode_from_args() -> OutputMode {
+    match std::env::args().nth(2).as_deref() {
+        None => OutputMode::Result,
+        Some("--dot") => OutputMode::Dot,
+        Some("--verilog") => OutputMode::Verilog,
+        Some(other) => {
+            eprintln!(
+                "Unknown output mode: {} (expected --dot or --verilog)",
+                other
+            );
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Parses the input into the initial wire values and the list of gates.
+fn parse_input(input: &str) -> (HashMap<&str, bool>, Vec<Gate<'_>>) {
     // Split the input into wire data and gate data
     let (wire_data, gate_data) = input.split_once("\n\n").unwrap();
 
     // Create a map of wire states (x, y) to boolean values
-    let _ = wire_data
+    let wires = wire_data
         .lines()
         .map(|line| {
             let parts = line.split_once(": ").unwrap();
@@ -53,9 +238,14 @@ fn solve(input: &str) -> String {
         })
         .collect::<Vec<_>>();
 
+    (wires, gates)
+}
+
+/// Finds the wires that break the ripple-carry adder patterns, sorted alphabetically.
+fn find_broken_nodes<'a>(gates: &[Gate<'a>]) -> Vec<&'a str> {
     // Create a map to track connections for each wire
     let mut connections: HashMap<&str, Vec<&str>> = HashMap::new();
-    for gate in &gates {
+    for gate in gates {
         connections.entry(gate.0).or_default().push(gate.2);
         connections.entry(gate.1).or_default().push(gate.2);
     }
@@ -63,10 +253,9 @@ fn solve(input: &str) -> String {
     let mut broken_nodes = HashSet::new();
 
     // Check each gate for broken node patterns
-    for gate in &gates {
+    for gate in gates {
         // z nodes must be XOR (except for z45)
         if gate.2.starts_with("z") && gate.2 != "z45" && gate.3 != GateLogic::Xor {
-            println!("Broken node detected: {}", gate.2); // Debugging
             broken_nodes.insert(gate.2);
         }
 
@@ -102,16 +291,146 @@ fn solve(input: &str) -> String {
         }
     }
 
-    // Sort the broken nodes alphabetically and return as a comma-separated string
+    // Sort the broken nodes alphabetically
     let mut broken_nodes = broken_nodes.into_iter().collect::<Vec<_>>();
     broken_nodes.sort();
-    broken_nodes.join(",")
+    broken_nodes
+}
+
+/// Solves the problem by analyzing the system of gates and wire connections.
+fn solve(input: &str) -> String {
+    let (_, gates) = parse_input(input);
+
+    // Return the broken nodes as a comma-separated string
+    find_broken_nodes(&gates).join(",")
+}
+
+/// Renders the circuit as a Graphviz DOT graph. Wires are drawn as ellipses, gates as boxes
+/// coloured by their logic, and the suspected swapped wires are highlighted in red.
+fn to_dot(wires: &HashMap<&str, bool>, gates: &[Gate<'_>], suspects: &[&str]) -> String {
+    let mut dot = String::from("digraph circuit {\n    rankdir=LR;\n    node [shape=ellipse];\n");
+
+    // Collect every wire name so each one is declared exactly once
+    let mut names = wires.keys().copied().collect::<Vec<_>>();
+    for gate in gates {
+        names.extend([gate.0, gate.1, gate.2]);
+    }
+    names.sort();
+    names.dedup();
+
+    for name in names {
+        if suspects.contains(&name) {
+            dot.push_str(&format!(
+                "    \"{}\" [style=filled, fillcolor=red, fontcolor=white];\n",
+                name
+            ));
+        } else if let Some(&value) = wires.get(name) {
+            dot.push_str(&format!(
+                "    \"{}\" [label=\"{}={}\", shape=doublecircle];\n",
+                name, name, value as u8
+            ));
+        } else {
+            dot.push_str(&format!("    \"{}\";\n", name));
+        }
+    }
+
+    // Each gate becomes its own node between the input and output wires
+    for (i, (input1, input2, output, logic)) in gates.iter().enumerate() {
+        let edge_style = if suspects.contains(output) {
+            " [color=red, penwidth=2]"
+        } else {
+            ""
+        };
+        dot.push_str(&format!(
+            "    g{} [label=\"{}\", shape=box, style=filled, fillcolor={}];\n",
+            i,
+            logic.name(),
+            logic.color()
+        ));
+        dot.push_str(&format!("    \"{}\" -> g{};\n", input1, i));
+        dot.push_str(&format!("    \"{}\" -> g{};\n", input2, i));
+        dot.push_str(&format!("    g{} -> \"{}\"{};\n", i, output, edge_style));
+    }
+
+    dot.push_str("}\n");
+    dot
+}
+
+/// Escapes wire names that collide with Verilog keywords.
+fn verilog_ident(name: &str) -> String {
+    if VERILOG_KEYWORDS.binary_search(&name).is_ok() {
+        format!("\\{} ", name)
+    } else {
+        name.to_string()
+    }
+}
+
+/// Renders the circuit as a structural Verilog module using gate primitives. The initial
+/// wire values become module inputs and the `z` wires become module outputs.
+fn to_verilog(wires: &HashMap<&str, bool>, gates: &[Gate<'_>]) -> String {
+    let mut inputs = wires.keys().copied().collect::<Vec<_>>();
+    inputs.sort();
+
+    let mut outputs = gates
+        .iter()
+        .map(|gate| gate.2)
+        .filter(|name| name.starts_with("z"))
+        .collect::<Vec<_>>();
+    outputs.sort();
+
+    let mut internal = gates
+        .iter()
+        .map(|gate| gate.2)
+        .filter(|name| !name.starts_with("z") && !wires.contains_key(name))
+        .collect::<Vec<_>>();
+    internal.sort();
+
+    // Module header with one port per line
+    let ports = inputs
+        .iter()
+        .map(|name| format!("    input wire {}", verilog_ident(name)))
+        .chain(
+            outputs
+                .iter()
+                .map(|name| format!("    output wire {}", verilog_ident(name))),
+        )
+        .collect::<Vec<_>>();
+    let mut verilog = format!("module circuit (\n{}\n);\n", ports.join(",\n"));
+
+    for name in &internal {
+        verilog.push_str(&format!("    wire {};\n", verilog_ident(name)));
+    }
+
+    // Gate primitives list the output first, followed by the inputs
+    for (i, (input1, input2, output, logic)) in gates.iter().enumerate() {
+        verilog.push_str(&format!(
+            "    {} g{} ({}, {}, {});\n",
+            logic.primitive(),
+            i,
+            verilog_ident(output),
+            verilog_ident(input1),
+            verilog_ident(input2)
+        ));
+    }
+
+    verilog.push_str("endmodule\n");
+    verilog
 }
 
 fn main() {
     let input = read_file_from_args();
 
-    println!("result: {}", solve(&input));
+    match read_mode_from_args() {
+        OutputMode::Result => println!("result: {}", solve(&input)),
+        OutputMode::Dot => {
+            let (wires, gates) = parse_input(&input);
+            print!("{}", to_dot(&wires, &gates, &find_broken_nodes(&gates)));
+        }
+        OutputMode::Verilog => {
+            let (wires, gates) = parse_input(&input);
+            print!("{}", to_verilog(&wires, &gates));
+        }
+    }
 }
 
 #[cfg(test)]
@@ -151,6 +470,60 @@ x05 AND y05 -> z00"#;
         );
     }
 
+    // Exporting a small netlist, with the suspected swapped wire highlighted
+    #[test]
+    fn test_to_dot() {
+        let input = "x00: 1\ny00: 0\n\nx00 XOR y00 -> abc\nx00 AND y00 -> z00\nabc OR abc -> z01";
+        let (wires, gates) = parse_input(input);
+        let dot = to_dot(&wires, &gates, &["z00"]);
+
+        assert!(dot.starts_with("digraph circuit {"));
+        assert!(dot.contains("\"x00\" [label=\"x00=1\", shape=doublecircle];"));
+        assert!(dot.contains("g0 [label=\"XOR\", shape=box, style=filled, fillcolor=gold];"));
+        assert!(dot.contains("g1 [label=\"AND\", shape=box, style=filled, fillcolor=lightblue];"));
+        assert!(dot.contains("\"z00\" [style=filled, fillcolor=red, fontcolor=white];"));
+        assert!(dot.contains("g1 -> \"z00\" [color=red, penwidth=2];"));
+        assert!(dot.contains("g2 -> \"z01\";"));
+        assert!(dot.ends_with("}\n"));
+    }
+
+    // Exporting the same netlist as a structural Verilog module
+    #[test]
+    fn test_to_verilog() {
+        let input = "x00: 1\ny00: 0\n\nx00 XOR y00 -> and\nx00 AND y00 -> z00\nand OR and -> z01";
+        let (wires, gates) = parse_input(input);
+        let expected = r#"module circuit (
+    input wire x00,
+    input wire y00,
+    output wire z00,
+    output wire z01
+);
+    wire \and ;
+    xor g0 (\and , x00, y00);
+    and g1 (z00, x00, y00);
+    or g2 (z01, \and , \and );
+endmodule
+"#;
+        assert_eq!(to_verilog(&wires, &gates), expected);
+    }
+
+    // Escaping every reserved word, not just the gate primitives
+    #[test]
+    fn test_verilog_keywords() {
+        assert!(VERILOG_KEYWORDS.windows(2).all(|pair| pair[0] < pair[1]));
+        for keyword in ["tri", "wor", "use", "cell"] {
+            assert_eq!(verilog_ident(keyword), format!("\\{} ", keyword));
+        }
+        assert_eq!(verilog_ident("abc"), "abc");
+
+        let input = "x00: 1\ny00: 0\n\nx00 AND y00 -> tri\ntri OR x00 -> z00";
+        let (wires, gates) = parse_input(input);
+        let verilog = to_verilog(&wires, &gates);
+        assert!(verilog.contains("    wire \\tri ;\n"));
+        assert!(verilog.contains("    and g0 (\\tri , x00, y00);\n"));
+        assert!(verilog.contains("    or g1 (z00, \\tri , x00);\n"));
+    }
+
     #[test]
     fn test_with_input() {
         let input = read_file_by_path("docs/challenge_2.txt");